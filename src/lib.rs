@@ -70,12 +70,66 @@
 //! # }
 //! ```
 //!
+//! Going the other way uses `TryFrom`, so `try_into()` works too. A failed
+//! conversion gives you an [`InvalidType`], which plays nicely with `?`.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate sum_type;
+//! use std::convert::TryInto;
+//! use sum_type::InvalidType;
+//! # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+//! #         First(u32), Second(String), Third(Vec<u8>), } }
+//!
+//! fn double(n: u32) -> u32 { n * 2 }
+//!
+//! fn annotated(thing: MySumType) -> Result<u32, InvalidType> {
+//!     let n: u32 = thing.try_into()?;
+//!     Ok(n + 1)
+//! }
+//!
+//! fn inferred(thing: MySumType) -> Result<u32, InvalidType> {
+//!     Ok(double(thing.try_into()?))
+//! }
+//!
+//! # fn main() {
+//! assert_eq!(annotated(MySumType::First(41)), Ok(42));
+//! assert_eq!(inferred(MySumType::First(21)), Ok(42));
+//!
+//! let err = inferred(MySumType::Second(String::from("nope"))).unwrap_err();
+//! assert_eq!(err.expected_variant, "First");
+//! assert_eq!(err.actual_variant, "Second");
+//! # }
+//! ```
+//!
+//! With the `std` feature enabled, [`InvalidType`] also implements
+//! `std::error::Error` so it can be boxed up with other errors.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate sum_type;
+//! # use std::convert::TryInto;
+//! # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+//! #         First(u32), Second(String), Third(Vec<u8>), } }
+//! # #[cfg(feature = "std")]
+//! fn parse_and_unwrap(text: &str) -> Result<u32, Box<dyn std::error::Error>> {
+//!     let first = MySumType::First(text.parse()?);
+//!     let n: u32 = first.try_into()?;
+//!     Ok(n)
+//! }
+//!
+//! # fn main() {
+//! # #[cfg(feature = "std")]
+//! assert_eq!(parse_and_unwrap("42").unwrap(), 42);
+//! # }
+//! ```
+//!
 //! # Assumptions
 //!
 //! You need to make sure your type has more than one variant, meaning the
 //! following example will fail to compile.
 //!
-//! ```rust,compile_fail
+//! ```rust,compile_fail,E0080
 //! # fn main() {}
 //! #[macro_use]
 //! extern crate sum_type;
@@ -87,21 +141,68 @@
 //! }
 //! ```
 //!
-//! The `compile_error!()` macro is used to give a (hopefully) useful error
-//! message.
+//! A constant assertion is used to give a (hopefully) useful error message.
+//! It is checked after any `#[cfg]`-gated variants have been removed.
+//!
+//! ```text
+//! error[E0080]: evaluation panicked: The `OneVariant` type must have more than one variant
+//!  --> src/lib.rs:3:1
+//!   |
+//! 3 | / sum_type!{
+//! 4 | |     pub enum OneVariant {
+//! 5 | |         First(String),
+//! 6 | |     }
+//! 7 | | }
+//!   | |_^ evaluation of `_` failed here
+//! ```
+//!
+//! Each payload is stored inline, so it needs to be `Sized`. Use a reference
+//! or a `Box` for things like `str` and `[u8]`. The most common cases (`str`,
+//! slices and trait objects) get an error saying exactly that.
 //!
 //! ```text
-//! error: The `OneVariant` type must have more than one variant
-//!   --> src/lib.rs:37:1
-//!    |
-//! 7  | / sum_type!{
-//! 8  | |     pub enum OneVariant {
-//! 9  | |         First(String),
-//! 10 | |     }
-//! 11 | | }
-//!    | |_^
-//!    |
-//!    = note: this error originates in a macro outside of the current crate
+//! error: The `Text` variant of `Unsized` can't hold a `str` directly because it is unsized, use something like `&'static str` or a `Box<str>` instead
+//! ```
+//!
+//! ```rust,compile_fail
+//! # fn main() {}
+//! # #[macro_use]
+//! # extern crate sum_type;
+//! sum_type!{
+//!     pub enum Unsized {
+//!         Text(str),
+//!         Number(u32),
+//!     }
+//! }
+//! ```
+//!
+//! References need to be `'static` (see below for lifetimes). That still
+//! covers things like string and byte literals, and because `'static` data
+//! implements `Any` the conversions and introspection work as usual.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate sum_type;
+//! use std::convert::TryFrom;
+//! use sum_type::SumType;
+//!
+//! sum_type! {
+//!     #[derive(Debug, Copy, Clone, PartialEq)]
+//!     pub enum Borrowed {
+//!         Text(&'static str),
+//!         Chunk(&'static [u8]),
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let bytes: &'static [u8] = b"Hello";
+//! let chunk = Borrowed::from(bytes);
+//!
+//! assert!(chunk.variant_is::<&'static [u8]>());
+//! assert!(!chunk.variant_is::<&'static str>());
+//! assert_eq!(<&[u8]>::try_from(chunk), Ok(&b"Hello"[..]));
+//! assert!(<&str>::try_from(chunk).is_err());
+//! # }
 //! ```
 //!
 //! Sum types containing generics, including lifetimes, or which are using
@@ -120,6 +221,20 @@
 //! }
 //! ```
 //!
+//! The same goes for const generics:
+//!
+//! ```rust,compile_fail
+//! # fn main() {}
+//! # #[macro_use]
+//! # extern crate sum_type;
+//! sum_type!{
+//!     pub enum Buf<const N: usize> {
+//!         Small([u8; N]),
+//!         Large(Vec<u8>),
+//!     }
+//! }
+//! ```
+//!
 //! And so will this:
 //!
 //! ```rust,compile_fail
@@ -134,6 +249,92 @@
 //! }
 //! ```
 //!
+//! Each variant holds exactly one value, so variants with multiple fields
+//! are rejected too.
+//!
+//! ```rust,compile_fail
+//! # fn main() {}
+//! # #[macro_use]
+//! # extern crate sum_type;
+//! sum_type!{
+//!     pub enum MultipleFields {
+//!         Pair(u32, String),
+//!         Single(f64),
+//!     }
+//! }
+//! ```
+//!
+//! Group the fields into a tuple instead (note the double parentheses). A
+//! tuple payload works like any other type, so both fields can be read back
+//! with [`SumType::downcast_ref()`] or by converting back with `TryFrom`.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate sum_type;
+//! use std::convert::TryFrom;
+//! use sum_type::SumType;
+//!
+//! sum_type!{
+//!     #[derive(Debug, Clone, PartialEq)]
+//!     pub enum TupleField {
+//!         Pair((u32, String)),
+//!         Point((i32, i32)),
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let pair = TupleField::from((42, String::from("Hello")));
+//! let &(number, ref text) = pair.downcast_ref::<(u32, String)>().unwrap();
+//!
+//! assert_eq!(number, 42);
+//! assert_eq!(text, "Hello");
+//!
+//! let point = TupleField::from((-1, 2));
+//! assert_eq!(point, TupleField::Point((-1, 2)));
+//! assert_eq!(<(i32, i32)>::try_from(point.clone()), Ok((-1, 2)));
+//! assert!(<(u32, String)>::try_from(point).is_err());
+//! # }
+//! ```
+//!
+//! Paths, generic types and associated types work as payloads too.
+//!
+//! ```rust
+//! # #[macro_use]
+//! # extern crate sum_type;
+//! use std::convert::TryFrom;
+//! use std::ops::Add;
+//! use sum_type::SumType;
+//!
+//! pub struct Meters(f64);
+//!
+//! impl Add for Meters {
+//!     type Output = f64;
+//!     fn add(self, other: Meters) -> f64 { self.0 + other.0 }
+//! }
+//!
+//! sum_type!{
+//!     #[derive(Debug, Clone, PartialEq)]
+//!     pub enum Paths {
+//!         Map(std::collections::BTreeMap<u32, u32>),
+//!         Distance(<Meters as Add>::Output),
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let mut map = std::collections::BTreeMap::new();
+//! map.insert(1, 2);
+//!
+//! let paths = Paths::from(map.clone());
+//! assert_eq!(paths.variant(), "Map");
+//! assert_eq!(paths.downcast_ref(), Some(&map));
+//! assert_eq!(std::collections::BTreeMap::try_from(paths), Ok(map));
+//!
+//! let distance = Paths::from(Meters(1.0) + Meters(2.0));
+//! assert_eq!(distance, Paths::Distance(3.0));
+//! assert_eq!(f64::try_from(distance), Ok(3.0));
+//! # }
+//! ```
+//!
 //! # Try From
 //!
 //! `TryFrom` is automatically implemented on your sum type to convert it back to one of its variant types.
@@ -157,15 +358,68 @@
 //!
 //! let err = as_vec_u8.unwrap_err();
 //! assert_eq!(err.expected_variant, "Third");
+//! assert_eq!(err.expected_type, "Vec<u8>");
 //! assert_eq!(err.actual_variant, "Second");
+//! assert_eq!(
+//!     err.to_string(),
+//!     "cannot convert `Second` to `Vec<u8>`, expected the `Third` variant"
+//! );
+//! # }
+//! ```
+//!
+//! When you don't care why a conversion failed, `Option<T>` also implements
+//! `From` for each variant type.
+//!
+//! ```rust
+//! #[macro_use]
+//! extern crate sum_type;
+//! # fn main() {
+//! # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+//! #         First(u32), Second(String), Third(Vec<u8>), } }
+//! let first = MySumType::First(52);
+//! assert_eq!(Option::<u32>::from(first.clone()), Some(52));
+//! assert_eq!(Option::<String>::from(first), None);
+//!
+//! let items = vec![MySumType::First(1), MySumType::Third(vec![2]), MySumType::First(3)];
+//! let numbers: Vec<u32> = items.into_iter().filter_map(Option::from).collect();
+//! assert_eq!(numbers, vec![1, 3]);
+//! # }
+//! ```
+//!
+//! The active payload is also available through the standard
+//! `AsRef<dyn Any>` trait.
+//!
+//! ```rust
+//! #[macro_use]
+//! extern crate sum_type;
+//! use std::any::Any;
+//!
+//! # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+//! #         First(u32), Second(String), Third(Vec<u8>), } }
+//! fn is_number(value: impl AsRef<dyn Any>) -> bool {
+//!     value.as_ref().is::<u32>()
+//! }
+//!
+//! # fn main() {
+//! let first = MySumType::First(52);
+//! assert!(is_number(&first));
+//! assert!(!is_number(&MySumType::Second(String::from("52"))));
 //! # }
 //! ```
 //!
+//! Plenty more can be generated on request, like a `VARIANT_TABLE` constant
+//! or `retag()` and `fold_ref()` methods. See the [options] on
+//! [`sum_type!()`] for everything that's available.
+//!
 //! The `generated_example` feature flag will create an example of our
 //! `MySumType` which can be viewed using `rustdoc`.
 //!
 //! [sum type]: https://www.schoolofhaskell.com/school/to-infinity-and-beyond/pick-of-the-week/sum-types
 //! [`SumType`]: trait.SumType.html
+//! [`InvalidType`]: struct.InvalidType.html
+//! [`SumType::downcast_ref()`]: trait.SumType.html#tymethod.downcast_ref
+//! [options]: macro.sum_type.html#options
+//! [`sum_type!()`]: macro.sum_type.html
 
 #![no_std]
 #![deny(
@@ -179,14 +433,237 @@
 // library (as `$crate::_core`).
 #[doc(hidden)]
 pub extern crate core as _core;
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+pub extern crate alloc as _alloc;
+#[cfg(feature = "std")]
+extern crate std;
+#[cfg(feature = "derive")]
+extern crate sum_type_macros;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub extern crate serde as _serde;
+#[cfg(feature = "serde_json")]
+#[doc(hidden)]
+pub extern crate serde_json as _serde_json;
+
+use core::any::{Any, TypeId};
+
+/// With the `derive` feature enabled, `#[derive(SumType)]` implements
+/// [`SumType`], `From` and `TryFrom` for an enum you have declared yourself.
+///
+/// Unlike [`sum_type!()`] this works with generics, lifetimes and any
+/// visibility, although only the core impls are generated and most of the
+/// `#[sum_type(...)]` options aren't supported. Downcasting goes through
+/// `Any`, so `SumType` is only implemented when every payload is `'static`.
+/// A variant holding a bare type parameter doesn't get `From` or `TryFrom`
+/// impls, because they could overlap with the other variants' impls.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # #[cfg(feature = "derive")]
+/// # mod example {
+/// use std::convert::TryFrom;
+/// use sum_type::SumType;
+///
+/// #[derive(Debug, PartialEq, SumType)]
+/// pub(crate) enum Either<L, R> {
+///     Left(L),
+///     Right(R),
+///     Neither(Vec<L>),
+/// }
+///
+/// #[derive(SumType)]
+/// enum Borrowed<'a> {
+///     Text(&'a str),
+///     Number(u32),
+/// }
+///
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Declarative {
+///         Left(u32),
+///         Right(String),
+///         Neither(Vec<u32>),
+///     }
+/// }
+///
+/// pub fn main() {
+///     let derived: Either<u32, String> = Either::Right(String::from("Hello"));
+///     let declarative = Declarative::from(String::from("Hello"));
+///
+///     assert_eq!(derived, Either::Right(String::from("Hello")));
+///     assert_eq!(derived.variant(), declarative.variant());
+///     assert_eq!(derived.variants(), declarative.variants());
+///     assert_eq!(derived.variant_index(), declarative.variant_index());
+///     assert_eq!(derived.payload_type_id(), declarative.payload_type_id());
+///     assert_eq!(derived.downcast_ref::<String>(), declarative.downcast_ref::<String>());
+///
+///     let nothing: Either<u32, String> = Either::from(vec![1, 2]);
+///     assert_eq!(nothing, Either::Neither(vec![1, 2]));
+///     assert_eq!(Vec::<u32>::try_from(nothing), Ok(vec![1, 2]));
+///
+///     let err = Vec::<u32>::try_from(derived).unwrap_err();
+///     assert_eq!(err.expected_variant, "Neither");
+///     assert_eq!(err.actual_variant, "Right");
+///     assert_eq!(err.all_variants, declarative.variants());
+///
+///     let text = String::from("borrowed");
+///     let borrowed = Borrowed::from(text.as_str());
+///     assert!(<&str>::try_from(borrowed).is_ok());
+///     assert_eq!(Borrowed::from("static").variant(), "Text");
+/// }
+/// # }
+/// # fn main() {
+/// # #[cfg(feature = "derive")]
+/// # example::main();
+/// # }
+/// ```
+///
+/// `#[derive(Clone)]` requires every type parameter to be `Clone`, even when
+/// the payloads don't need it. Adding `#[sum_type(clone)]` generates a `Clone`
+/// impl which is only bounded on the payload types instead.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # #[cfg(feature = "derive")]
+/// # mod example {
+/// use std::rc::Rc;
+/// use sum_type::SumType;
+///
+/// /// Not `Clone`, but an `Rc` pointing to one is.
+/// #[derive(Debug, PartialEq)]
+/// pub struct Handle(u32);
+///
+/// #[derive(Debug, PartialEq, SumType)]
+/// #[sum_type(clone)]
+/// pub enum Shared<T> {
+///     Pointer(Rc<T>),
+///     Count(u32),
+/// }
+///
+/// pub fn main() {
+///     let original: Shared<Handle> = Shared::from(Rc::new(Handle(7)));
+///     let copy = original.clone();
+///
+///     assert_eq!(copy, original);
+///     assert_eq!(copy.variant(), "Pointer");
+/// }
+/// # }
+/// # fn main() {
+/// # #[cfg(feature = "derive")]
+/// # example::main();
+/// # }
+/// ```
+///
+/// Both front-ends spell payload types the same way in an [`InvalidType`]
+/// error.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # #[cfg(feature = "derive")]
+/// # mod example {
+/// use std::convert::TryFrom;
+/// use sum_type::SumType;
+///
+/// #[derive(SumType)]
+/// pub enum Derived {
+///     Bytes(Vec<u8>),
+///     Pair((u32, &'static str)),
+///     Array([u8; 4]),
+///     Callback(Box<dyn Fn(u32) -> u32 + Send>),
+/// }
+///
+/// sum_type! {
+///     pub enum Declarative {
+///         Bytes(Vec<u8>),
+///         Pair((u32, &'static str)),
+///         Array([u8; 4]),
+///         Callback(Box<dyn Fn(u32) -> u32 + Send>),
+///     }
+/// }
+///
+/// pub fn main() {
+///     let derived = [
+///         Vec::<u8>::try_from(Derived::Array([0; 4])).unwrap_err(),
+///         <(u32, &str)>::try_from(Derived::Array([0; 4])).unwrap_err(),
+///         <[u8; 4]>::try_from(Derived::Bytes(vec![])).unwrap_err(),
+///         <Box<dyn Fn(u32) -> u32 + Send>>::try_from(Derived::Bytes(vec![])).err().unwrap(),
+///     ];
+///     let declarative = [
+///         Vec::<u8>::try_from(Declarative::Array([0; 4])).unwrap_err(),
+///         <(u32, &str)>::try_from(Declarative::Array([0; 4])).unwrap_err(),
+///         <[u8; 4]>::try_from(Declarative::Bytes(vec![])).unwrap_err(),
+///         <Box<dyn Fn(u32) -> u32 + Send>>::try_from(Declarative::Bytes(vec![])).err().unwrap(),
+///     ];
+///
+///     for (derived, declarative) in derived.iter().zip(&declarative) {
+///         assert_eq!(derived.expected_type, declarative.expected_type);
+///     }
+///     assert_eq!(derived[0].expected_type, "Vec<u8>");
+///     assert_eq!(derived[1].expected_type, "(u32, &'static str)");
+/// }
+/// # }
+/// # fn main() {
+/// # #[cfg(feature = "derive")]
+/// # example::main();
+/// # }
+/// ```
+///
+/// [`SumType`]: trait.SumType.html
+/// [`sum_type!()`]: macro.sum_type.html
+/// [`InvalidType`]: struct.InvalidType.html
+#[cfg(feature = "derive")]
+pub use sum_type_macros::SumType;
 
-use core::any::Any;
+// Only the `sum_type!()` macro and `#[derive(SumType)]` should implement
+// `SumType`, because things like `defer!()` rely on its invariants. The
+// generated impls need to name `Sealed` from the user's crate, so it has to
+// be public, but nothing in here is part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub trait Sealed {}
+}
 
 /// The result of a failed conversion from `TryFrom`.
+///
+/// # Note
+///
+/// Only `sum_type` is meant to create an `InvalidType`, and fields may be
+/// added in minor releases, so destructure it with a `..` rest pattern. The
+/// `expected_type` field was added in 0.3, which was a breaking change for
+/// code that built an `InvalidType` by hand or destructured it without `..`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use std::convert::TryFrom;
+/// use sum_type::InvalidType;
+///
+/// sum_type! {
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let InvalidType { expected_variant, expected_type, .. } =
+///     u32::try_from(MySumType::Second(String::from("Hello"))).unwrap_err();
+///
+/// assert_eq!(expected_variant, "First");
+/// assert_eq!(expected_type, "u32");
+/// # }
+/// ```
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct InvalidType {
     /// The variant this conversion is valid for.
     pub expected_variant: &'static str,
+    /// The name of the type being converted to, as written in the variant.
+    pub expected_type: &'static str,
     /// The actual variant.
     pub actual_variant: &'static str,
     /// All possible variants.
@@ -195,199 +672,5937 @@ pub struct InvalidType {
     pub __non_exhaustive: (),
 }
 
-/// Various methods for introspection and dynamic typing.
-///
-/// # Note
-///
-/// This trait is automatically implemented for all types generated by the
-/// `sum_type!()` macro. You should never need to implement it manually.
-pub trait SumType {
-    /// The name of the current variant.
-    fn variant(&self) -> &'static str;
-    /// A list of all possible variants.
-    fn variants(&self) -> &'static [&'static str];
-    /// Try to get a reference to the inner field if it is a `T`.
-    fn downcast_ref<T: Any>(&self) -> Option<&T>;
-    /// Return a mutable reference to the inner field if it is a `T`.
-    fn downcast_mut<T: Any>(&mut self) -> Option<&mut T>;
-    /// Is the underlying variant an instance of `T`?
-    fn variant_is<T: Any>(&self) -> bool;
+impl InvalidType {
+    /// The error for when `value` doesn't hold a `T`, naming whichever variant
+    /// does. If none of them do, `expected_variant` is empty and
+    /// `expected_type` falls back to `T`'s full type name.
+    fn wrong_type<S: SumType + ?Sized, T: Any>(value: &S) -> InvalidType {
+        let (expected_variant, expected_type) = value
+            .__variant_for_type(TypeId::of::<T>())
+            .unwrap_or(("", core::any::type_name::<T>()));
+
+        InvalidType {
+            expected_variant,
+            expected_type,
+            actual_variant: value.variant(),
+            all_variants: value.variants(),
+            __non_exhaustive: (),
+        }
+    }
 }
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! __sum_type_try_from {
-    ($enum_name:ident, $( $name:ident => $variant_type:ty ),*) => {
-       $(
-            impl $crate::_core::convert::TryFrom<$enum_name> for $variant_type {
-                type Error = $crate::InvalidType;
-
-                fn try_from(other: $enum_name) -> Result<$variant_type, Self::Error> {
-                    let variant = $crate::SumType::variant(&other);
-                    let variants = $crate::SumType::variants(&other);
-
-                    if let $enum_name::$name(value) = other {
-                        Ok(value)
-                    } else {
-                        Err($crate::InvalidType {
-                            expected_variant: stringify!($name),
-                            actual_variant: variant,
-                            all_variants: variants,
-                            __non_exhaustive: (),
-                        })
-                    }
-                }
+impl core::fmt::Display for InvalidType {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if self.expected_variant.is_empty() {
+            return write!(
+                f,
+                "cannot convert `{}` to `{}`, no variant holds that type",
+                self.actual_variant, self.expected_type
+            );
+        }
+
+        write!(
+            f,
+            "cannot convert `{}` to `{}`, expected the `{}` variant",
+            self.actual_variant, self.expected_type, self.expected_variant
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidType {}
+
+/// The result of failing to parse a sum type from a string.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ParseError {
+    /// The variant which was named, if its payload failed to parse.
+    pub variant: Option<&'static str>,
+    /// All possible variants.
+    pub all_variants: &'static [&'static str],
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if let Some(variant) = self.variant {
+            return write!(f, "unable to parse the payload for the `{}` variant", variant);
+        }
+
+        write!(f, "unknown variant, expected one of ")?;
+
+        for (i, name) in self.all_variants.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "`{}`", name)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// The result of failing to move a sum type's payload into another variant
+/// with `retag()`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RetagError {
+    /// There is no variant with that name.
+    UnknownVariant(ParseError),
+    /// The named variant holds a different type.
+    WrongType(InvalidType),
+}
+
+impl core::fmt::Display for RetagError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match *self {
+            RetagError::UnknownVariant(ref e) => e.fmt(f),
+            RetagError::WrongType(ref e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RetagError {}
+
+/// The result of failing to combine values with `try_sum()` or
+/// `try_product()`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CombineError {
+    /// There were no values to combine, so there's no variant to take the
+    /// identity from.
+    Empty,
+    /// The values didn't all hold the same variant.
+    MixedVariants(InvalidType),
+}
+
+impl core::fmt::Display for CombineError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match *self {
+            CombineError::Empty => write!(f, "there were no values to combine"),
+            CombineError::MixedVariants(ref e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CombineError {}
+
+/// Information about one of a sum type's variants, as found in its
+/// `VARIANTS_META` table.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct VariantInfo {
+    /// The variant's name.
+    pub name: &'static str,
+    /// The name of the payload type, as written in the variant.
+    pub type_name: &'static str,
+    /// The variant's index, in declaration order.
+    pub index: usize,
+    /// The variant's doc comment, without the space after each line's `///`
+    /// or any surrounding blank lines.
+    pub doc: Option<&'static str>,
+    #[doc(hidden)]
+    pub __non_exhaustive: (),
+}
+
+/// A chain of attempts at getting a value out of a sum type, created by
+/// [`SumType::try_cast()`].
+///
+/// [`SumType::try_cast()`]: trait.SumType.html#method.try_cast
+#[derive(Debug, Copy, Clone)]
+pub struct CastResult<'a, S: ?Sized + 'a, T> {
+    source: &'a S,
+    value: Option<T>,
+}
+
+impl<'a, S: SumType + ?Sized + 'a, T> CastResult<'a, S, T> {
+    /// If nothing has matched so far, try again by converting a `U`.
+    pub fn or_else<U: Any, F: FnOnce(&'a U) -> T>(self, f: F) -> CastResult<'a, S, T> {
+        let CastResult { source, value } = self;
+        let value = value.or_else(|| source.downcast_ref::<U>().map(f));
+
+        CastResult { source, value }
+    }
+
+    /// Transform the value, if there is one.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> CastResult<'a, S, U> {
+        CastResult {
+            source: self.source,
+            value: self.value.map(f),
+        }
+    }
+
+    /// The value from the first successful attempt.
+    pub fn ok(self) -> Option<T> {
+        self.value
+    }
+}
+
+/// Various methods for introspection and dynamic typing.
+///
+/// # Note
+///
+/// This trait is automatically implemented for all types generated by the
+/// `sum_type!()` macro or `#[derive(SumType)]`, and it is sealed so it can't
+/// be implemented manually. Earlier releases allowed manual impls, so this
+/// was a breaking change in 0.3.
+///
+/// ```rust,compile_fail
+/// use std::any::Any;
+/// use sum_type::SumType;
+///
+/// struct NotGenerated(u32);
+///
+/// impl SumType for NotGenerated {
+///     fn variant(&self) -> &'static str { "NotGenerated" }
+///     fn variants(&self) -> &'static [&'static str] { &["NotGenerated"] }
+///     fn variant_index(&self) -> usize { 0 }
+///     fn payload_type_id(&self) -> std::any::TypeId { std::any::TypeId::of::<u32>() }
+///     fn downcast_ref<T: Any>(&self) -> Option<&T> { (&self.0 as &dyn Any).downcast_ref() }
+///     fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> { (&mut self.0 as &mut dyn Any).downcast_mut() }
+///     fn variant_is<T: Any>(&self) -> bool { self.downcast_ref::<T>().is_some() }
+/// }
+/// ```
+pub trait SumType: __private::Sealed {
+    /// The name of the current variant.
+    fn variant(&self) -> &'static str;
+    /// A list of all possible variants.
+    fn variants(&self) -> &'static [&'static str];
+    /// The index of the current variant, in declaration order.
+    fn variant_index(&self) -> usize;
+    /// The `TypeId` of the inner field.
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use std::any::TypeId;
+    /// use sum_type::SumType;
+    /// # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+    /// #         First(u32), Second(String), Third(Vec<u8>), } }
+    ///
+    /// # fn main() {
+    /// let second = MySumType::Second(String::from("Hello"));
+    ///
+    /// assert_eq!(second.payload_type_id(), TypeId::of::<String>());
+    /// assert_ne!(second.payload_type_id(), TypeId::of::<u32>());
+    /// # }
+    /// ```
+    fn payload_type_id(&self) -> TypeId;
+    /// Try to get a reference to the inner field if it is a `T`.
+    fn downcast_ref<T: Any>(&self) -> Option<&T>;
+    /// Return a mutable reference to the inner field if it is a `T`.
+    fn downcast_mut<T: Any>(&mut self) -> Option<&mut T>;
+    /// Is the underlying variant an instance of `T`?
+    fn variant_is<T: Any>(&self) -> bool;
+
+    /// The name and payload type (as written) of the variant holding a
+    /// `type_id`, if there is one.
+    #[doc(hidden)]
+    fn __variant_for_type(&self, type_id: TypeId) -> Option<(&'static str, &'static str)>;
+
+    /// Get a reference to the inner field if it is a `T`, falling back to
+    /// `default` otherwise.
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use sum_type::SumType;
+    /// # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+    /// #         First(u32), Second(String), Third(Vec<u8>), } }
+    ///
+    /// # fn main() {
+    /// let first = MySumType::First(52);
+    ///
+    /// assert_eq!(first.downcast_ref_or(&0_u32), &52);
+    /// assert_eq!(first.downcast_ref_or(&String::from("default")), "default");
+    /// # }
+    /// ```
+    fn downcast_ref_or<'a, T: Any>(&'a self, default: &'a T) -> &'a T {
+        self.downcast_ref::<T>().unwrap_or(default)
+    }
+
+    /// Run `f` on the inner field if it is a `T`, returning its result.
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use sum_type::SumType;
+    /// # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+    /// #         First(u32), Second(String), Third(Vec<u8>), } }
+    ///
+    /// # fn main() {
+    /// let mut second = MySumType::Second(String::from("Hello"));
+    ///
+    /// let len = second.with_variant_mut(|s: &mut String| {
+    ///     s.push_str(" World");
+    ///     s.len()
+    /// });
+    /// assert_eq!(len, Some(11));
+    /// assert_eq!(second, MySumType::Second(String::from("Hello World")));
+    ///
+    /// assert_eq!(second.with_variant_mut(|n: &mut u32| *n += 1), None);
+    /// # }
+    /// ```
+    fn with_variant_mut<T: Any, R, F: FnOnce(&mut T) -> R>(&mut self, f: F) -> Option<R> {
+        self.downcast_mut::<T>().map(f)
+    }
+
+    /// Apply `f` to the inner field if it is a `T`, without needing mutable
+    /// access.
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use sum_type::SumType;
+    /// # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+    /// #         First(u32), Second(String), Third(Vec<u8>), } }
+    ///
+    /// # fn main() {
+    /// let values = vec![
+    ///     MySumType::Second(String::from("Hello")),
+    ///     MySumType::First(42),
+    /// ];
+    ///
+    /// let lengths: Vec<Option<usize>> = values
+    ///     .iter()
+    ///     .map(|v| v.map_variant_ref(|s: &String| s.len()))
+    ///     .collect();
+    /// assert_eq!(lengths, vec![Some(5), None]);
+    /// # }
+    /// ```
+    fn map_variant_ref<T: Any, U, F: FnOnce(&T) -> U>(&self, f: F) -> Option<U> {
+        self.downcast_ref::<T>().map(f)
+    }
+
+    /// Run `f` on the inner field if it is a `T`, otherwise return an
+    /// [`InvalidType`] saying what was found instead.
+    ///
+    /// The error names the variant which holds a `T`. If no variant does,
+    /// `expected_variant` is empty and `expected_type` is `T`'s full type
+    /// name.
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use sum_type::SumType;
+    /// # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+    /// #         First(u32), Second(String), Third(Vec<u8>), } }
+    ///
+    /// # fn main() {
+    /// let second = MySumType::Second(String::from("Hello"));
+    ///
+    /// assert_eq!(second.with_downcast(|s: &String| s.len()), Ok(5));
+    ///
+    /// let err = second.with_downcast(|n: &u32| *n + 1).unwrap_err();
+    /// assert_eq!(err.expected_variant, "First");
+    /// assert_eq!(err.expected_type, "u32");
+    /// assert_eq!(err.actual_variant, "Second");
+    /// assert_eq!(err.all_variants, &["First", "Second", "Third"]);
+    ///
+    /// let err = second.with_downcast(|b: &bool| !*b).unwrap_err();
+    /// assert_eq!(err.expected_variant, "");
+    /// assert_eq!(err.expected_type, "bool");
+    /// # }
+    /// ```
+    ///
+    /// [`InvalidType`]: struct.InvalidType.html
+    fn with_downcast<T: Any, R, F: FnOnce(&T) -> R>(&self, f: F) -> Result<R, InvalidType> {
+        match self.downcast_ref::<T>() {
+            Some(value) => Ok(f(value)),
+            None => Err(InvalidType::wrong_type::<Self, T>(self)),
+        }
+    }
+
+    /// Convert into another type using its `From` impl.
+    ///
+    /// This is just `U::from(self)`, but reads better at the end of a chain
+    /// and picks the target when several `Into` impls would apply.
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use sum_type::SumType;
+    ///
+    /// sum_type! {
+    ///     #[derive(Debug, PartialEq)]
+    ///     pub enum Small {
+    ///         First(u32),
+    ///         Second(String),
+    ///     }
+    /// }
+    ///
+    /// sum_type! {
+    ///     #[derive(Debug, PartialEq)]
+    ///     pub enum Big {
+    ///         First(u32),
+    ///         Second(String),
+    ///         Third(f64),
+    ///     }
+    /// }
+    ///
+    /// impl From<Small> for Big {
+    ///     fn from(small: Small) -> Big {
+    ///         match small {
+    ///             Small::First(value) => Big::First(value),
+    ///             Small::Second(value) => Big::Second(value),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// let big: Big = Small::from(42).into_other();
+    /// assert_eq!(big, Big::First(42));
+    /// assert_eq!(Small::from(String::from("Hi")).into_other::<Big>().variant(), "Second");
+    /// # }
+    /// ```
+    fn into_other<U: From<Self>>(self) -> U
+    where
+        Self: Sized,
+    {
+        U::from(self)
+    }
+
+    /// Start a chain of attempts at getting a value out of the inner field,
+    /// beginning with a reference to it as a `T`.
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use sum_type::SumType;
+    /// # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+    /// #         First(u32), Second(String), Third(Vec<u8>), } }
+    ///
+    /// # fn main() {
+    /// let third = MySumType::Third(vec![1, 2, 3]);
+    ///
+    /// let len = third
+    ///     .try_cast::<String>()
+    ///     .map(|s| s.len())
+    ///     .or_else(|bytes: &Vec<u8>| bytes.len())
+    ///     .ok();
+    /// assert_eq!(len, Some(3));
+    ///
+    /// let number = third.try_cast::<u32>().map(|n| *n).or_else(|n: &u8| u32::from(*n));
+    /// assert_eq!(number.ok(), None);
+    /// # }
+    /// ```
+    fn try_cast<'a, T: Any>(&'a self) -> CastResult<'a, Self, &'a T> {
+        CastResult {
+            source: self,
+            value: self.downcast_ref::<T>(),
+        }
+    }
+
+    /// Extend the inner field with the contents of an iterator, if it is a
+    /// `C`.
+    ///
+    /// When the inner field isn't a `C`, the error names the variant which
+    /// holds a `C`. If no variant does, `expected_variant` is empty and
+    /// `expected_type` is `C`'s full type name.
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use sum_type::SumType;
+    /// # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+    /// #         First(u32), Second(String), Third(Vec<u8>), } }
+    ///
+    /// # fn main() {
+    /// let mut third = MySumType::Third(vec![1, 2, 3]);
+    /// third.try_extend::<Vec<u8>, _>(4..6).unwrap();
+    /// assert_eq!(third, MySumType::Third(vec![1, 2, 3, 4, 5]));
+    ///
+    /// let mut first = MySumType::First(42);
+    /// let err = first.try_extend::<Vec<u8>, _>(4..6).unwrap_err();
+    /// assert_eq!(err.actual_variant, "First");
+    /// assert_eq!(err.expected_variant, "Third");
+    /// assert_eq!(err.expected_type, "Vec<u8>");
+    ///
+    /// let err = first.try_extend::<Vec<u32>, _>(4..6).unwrap_err();
+    /// assert_eq!(err.expected_variant, "");
+    /// assert!(err.expected_type.ends_with("Vec<u32>"));
+    /// assert!(err.to_string().ends_with("no variant holds that type"));
+    /// # }
+    /// ```
+    fn try_extend<C, I>(&mut self, iter: I) -> Result<(), InvalidType>
+    where
+        C: Any + Extend<I::Item>,
+        I: IntoIterator,
+    {
+        match self.downcast_mut::<C>() {
+            Some(collection) => {
+                collection.extend(iter);
+                Ok(())
+            }
+            None => Err(InvalidType::wrong_type::<Self, C>(self)),
+        }
+    }
+
+    /// Is the underlying variant called `name` and an instance of `T`?
+    ///
+    /// Unlike [`variant_is()`], this can tell apart variants which hold the
+    /// same type.
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use sum_type::SumType;
+    ///
+    /// sum_type! {
+    ///     pub enum Temperature {
+    ///         Celsius(u32),
+    ///         #[sum_type(skip_from)]
+    ///         Fahrenheit(u32),
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// let hot = Temperature::Celsius(40);
+    ///
+    /// assert!(hot.variant_is::<u32>());
+    /// assert!(hot.variant_is_named::<u32>("Celsius"));
+    /// assert!(!hot.variant_is_named::<u32>("Fahrenheit"));
+    /// assert!(!hot.variant_is_named::<f32>("Celsius"));
+    /// # }
+    /// ```
+    ///
+    /// [`variant_is()`]: #tymethod.variant_is
+    fn variant_is_named<T: Any>(&self, name: &str) -> bool {
+        self.variant_is::<T>() && self.variant() == name
+    }
+
+    /// Do both values hold the same variant, regardless of their payloads?
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use sum_type::SumType;
+    /// # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+    /// #         First(u32), Second(String), Third(Vec<u8>), } }
+    ///
+    /// # fn main() {
+    /// let first = MySumType::First(1);
+    ///
+    /// assert!(first.same_variant(&MySumType::First(999)));
+    /// assert!(!first.same_variant(&MySumType::Second(String::from("1"))));
+    /// # }
+    /// ```
+    fn same_variant(&self, other: &Self) -> bool {
+        self.variant_index() == other.variant_index()
+    }
+
+    /// Is the current variant one of `names`?
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate sum_type;
+    /// use sum_type::SumType;
+    /// # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+    /// #         First(u32), Second(String), Third(Vec<u8>), } }
+    ///
+    /// # fn main() {
+    /// let second = MySumType::Second(String::from("Hello"));
+    ///
+    /// assert!(second.is_any_of(&["First", "Second"]));
+    /// assert!(!second.is_any_of(&["First", "Third"]));
+    /// assert!(!second.is_any_of(&[]));
+    /// # }
+    /// ```
+    fn is_any_of(&self, names: &[&str]) -> bool {
+        names.contains(&self.variant())
+    }
+}
+
+/// Iterate over the payloads in a list of sum types which are a `T`,
+/// skipping everything else.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # sum_type! { #[derive(Debug, Clone, PartialEq)] pub enum MySumType {
+/// #         First(u32), Second(String), Third(Vec<u8>), } }
+/// # fn main() {
+/// let events = vec![
+///     MySumType::First(1),
+///     MySumType::Second(String::from("Hello")),
+///     MySumType::First(2),
+///     MySumType::Third(vec![3]),
+/// ];
+///
+/// let numbers: Vec<&u32> = sum_type::filter_downcast(&events).collect();
+/// assert_eq!(numbers, vec![&1, &2]);
+///
+/// assert_eq!(sum_type::filter_downcast::<f32, _>(&events).count(), 0);
+/// # }
+/// ```
+pub fn filter_downcast<T: Any, S: SumType>(items: &[S]) -> impl Iterator<Item = &T> {
+    items.iter().filter_map(SumType::downcast_ref::<T>)
+}
+
+/// String equality which can be used in a `const fn`.
+#[doc(hidden)]
+pub const fn __const_str_eq(left: &str, right: &str) -> bool {
+    let left = left.as_bytes();
+    let right = right.as_bytes();
+
+    if left.len() != right.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < left.len() {
+        if left[i] != right[i] {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+/// Are two `stringify!()`-ed types written the same way, ignoring spaces?
+#[doc(hidden)]
+pub const fn __const_type_eq(left: &str, right: &str) -> bool {
+    let left = left.as_bytes();
+    let right = right.as_bytes();
+    let mut i = 0;
+    let mut j = 0;
+
+    loop {
+        while i < left.len() && left[i] == b' ' {
+            i += 1;
+        }
+        while j < right.len() && right[j] == b' ' {
+            j += 1;
+        }
+
+        if i == left.len() || j == right.len() {
+            return i == left.len() && j == right.len();
+        }
+        if left[i] != right[j] {
+            return false;
+        }
+
+        i += 1;
+        j += 1;
+    }
+}
+
+/// Like [`__const_str_eq()`], but ignoring ASCII case.
+#[doc(hidden)]
+pub const fn __const_str_eq_ignore_ascii_case(left: &str, right: &str) -> bool {
+    // u8::to_ascii_lowercase() isn't a const fn on older compilers
+    const fn lower(b: u8) -> u8 {
+        if b >= b'A' && b <= b'Z' {
+            b + (b'a' - b'A')
+        } else {
+            b
+        }
+    }
+
+    let left = left.as_bytes();
+    let right = right.as_bytes();
+
+    if left.len() != right.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < left.len() {
+        if lower(left[i]) != lower(right[i]) {
+            return false;
+        }
+        i += 1;
+    }
+
+    true
+}
+
+/// Which bytes of a doc comment survive normalising, as a `start..end` range
+/// over the bytes left after removing one leading space from each line.
+const fn doc_bounds(raw: &[u8]) -> (usize, usize) {
+    const fn is_space(b: u8) -> bool {
+        b == b' ' || b == b'\t' || b == b'\n' || b == b'\r'
+    }
+
+    let mut start = None;
+    let mut end = 0;
+    let mut kept = 0;
+    let mut line_start = true;
+    let mut i = 0;
+
+    while i < raw.len() {
+        let b = raw[i];
+        i += 1;
+
+        if line_start && b == b' ' {
+            line_start = false;
+            continue;
+        }
+        line_start = b == b'\n';
+
+        if !is_space(b) {
+            if start.is_none() {
+                start = Some(kept);
+            }
+            end = kept + 1;
+        }
+        kept += 1;
+    }
+
+    match start {
+        Some(start) => (start, end),
+        None => (0, 0),
+    }
+}
+
+/// The length of a doc comment once [`__doc_normalize()`] is done with it.
+#[doc(hidden)]
+pub const fn __doc_len(raw: &str) -> usize {
+    let (start, end) = doc_bounds(raw.as_bytes());
+    end - start
+}
+
+/// Tidy up a doc comment the same way rustdoc does, removing the space after
+/// each line's `///` and any blank lines around the text.
+#[doc(hidden)]
+pub const fn __doc_normalize<const N: usize>(raw: &str) -> [u8; N] {
+    let raw = raw.as_bytes();
+    let (start, _) = doc_bounds(raw);
+    let mut normalized = [0; N];
+    let mut kept = 0;
+    let mut line_start = true;
+    let mut i = 0;
+
+    while i < raw.len() {
+        let b = raw[i];
+        i += 1;
+
+        if line_start && b == b' ' {
+            line_start = false;
+            continue;
+        }
+        line_start = b == b'\n';
+
+        if kept >= start && kept - start < N {
+            normalized[kept - start] = b;
+        }
+        kept += 1;
+    }
+
+    normalized
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_try_from {
+    ($enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( $crate::__sum_type_try_from!(@variant $enum_name, $fn_attrs, $impl_attrs, $name ($label) => $variant_type); )*
+    };
+    (@variant $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::TryFrom<$enum_name> for $variant_type {
+            type Error = $crate::InvalidType;
+
+            $( #[$fn_attr] )*
+            fn try_from(other: $enum_name) -> $crate::_core::result::Result<$variant_type, Self::Error> {
+                let variant = $crate::SumType::variant(&other);
+                let variants = $crate::SumType::variants(&other);
+
+                if let $enum_name::$name(value) = other {
+                    $crate::_core::result::Result::Ok(value)
+                } else {
+                    $crate::_core::result::Result::Err($crate::InvalidType {
+                        expected_variant: $label,
+                        expected_type: stringify!($variant_type),
+                        actual_variant: variant,
+                        all_variants: variants,
+                        __non_exhaustive: (),
+                    })
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_into_option {
+    ($enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( $crate::__sum_type_into_option!(@variant $enum_name, $fn_attrs, $impl_attrs, $name ($label) => $variant_type); )*
+    };
+    (@variant $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::From<$enum_name> for $crate::_core::option::Option<$variant_type> {
+            $( #[$fn_attr] )*
+            fn from(other: $enum_name) -> $crate::_core::option::Option<$variant_type> {
+                match other {
+                    $enum_name::$name(value) => $crate::_core::option::Option::Some(value),
+                    #[allow(unreachable_patterns)]
+                    _ => $crate::_core::option::Option::None,
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_from {
+    ($enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( $crate::__sum_type_from!(@variant $enum_name, $fn_attrs, $impl_attrs, $name ($label) => $variant_type); )*
+    };
+    (@variant $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::From<$variant_type> for $enum_name {
+            $( #[$fn_attr] )*
+            fn from(other: $variant_type) -> $enum_name {
+                $enum_name::$name(other)
+            }
+        }
+    };
+}
+
+// The conversions to and from each variant's type, unless the variant has
+// opted out of them with `skip_from`. With `from_variant_attr` on the enum,
+// variants have to opt in with `from` instead. `partial_eq_option` follows the
+// same rules, because it would clash in the same way.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_conversions {
+    (@mode [(from_variant_attr) $($rest:tt)*], $var_opts:tt, $($args:tt)*) => {
+        $crate::__sum_type_conversions!(@opt_in $var_opts, $($args)*);
+    };
+    (@mode [$other:tt $($rest:tt)*], $var_opts:tt, $($args:tt)*) => {
+        $crate::__sum_type_conversions!(@mode [$($rest)*], $var_opts, $($args)*);
+    };
+    (@mode [], $var_opts:tt, $($args:tt)*) => {
+        $crate::__sum_type_conversions!(@find $var_opts, $($args)*);
+    };
+    (@opt_in [(from) $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_conversions!(@find [], $($args)*);
+    };
+    (@opt_in [$other:tt $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_conversions!(@opt_in [$($rest)*], $($args)*);
+    };
+    (@opt_in [], $($args:tt)*) => {};
+    (@find [(skip_from) $($rest:tt)*], $($args:tt)*) => {};
+    (@find [$other:tt $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_conversions!(@find [$($rest)*], $($args)*);
+    };
+    (@find [], $enum_opts:tt, $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {
+        $crate::__sum_type_from!(@variant $enum_name, $fn_attrs, $impl_attrs, $name ($label) => $variant_type);
+        $crate::__sum_type_try_from!(@variant $enum_name, $fn_attrs, $impl_attrs, $name ($label) => $variant_type);
+        $crate::__sum_type_into_option!(@variant $enum_name, $fn_attrs, $impl_attrs, $name ($label) => $variant_type);
+        $crate::__sum_type_partial_eq_option!(@find $enum_opts, $enum_name, $fn_attrs, $impl_attrs, $name ($label) => $variant_type);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_trait {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::__private::Sealed for $enum_name {}
+
+        $( #[$impl_attr] )*
+        impl $crate::SumType for $enum_name {
+            $( #[$fn_attr] )*
+            fn variants(&self) -> &'static [ &'static str] {
+                &[
+                    $( $label ),*
+                ]
+            }
+
+            $( #[$fn_attr] )*
+            fn variant(&self) ->  &'static str {
+                match *self {
+                    $(
+                        $enum_name::$name(_) => $label,
+                    )*
+                }
+            }
+
+            $( #[$fn_attr] )*
+            fn variant_index(&self) -> usize {
+                $crate::__sum_type_variant_index!($( $name ),*);
+
+                match *self {
+                    $(
+                        $enum_name::$name(_) => VariantIndex::$name as usize,
+                    )*
+                }
+            }
+
+            $( #[$fn_attr] )*
+            fn payload_type_id(&self) -> $crate::_core::any::TypeId {
+                match *self {
+                    $(
+                        $enum_name::$name(_) => $crate::_core::any::TypeId::of::<$variant_type>(),
+                    )*
+                }
+            }
+
+            $( #[$fn_attr] )*
+            fn downcast_ref<T: $crate::_core::any::Any>(&self) -> $crate::_core::option::Option<&T> {
+                use $crate::_core::any::Any;
+
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => (value as &dyn Any).downcast_ref::<T>(),
+                    )*
+                }
+            }
+
+            $( #[$fn_attr] )*
+            fn downcast_mut<T: $crate::_core::any::Any>(&mut self) -> $crate::_core::option::Option<&mut T> {
+                use $crate::_core::any::Any;
+
+                match *self {
+                    $(
+                        $enum_name::$name(ref mut value) => (value as &mut dyn Any).downcast_mut::<T>(),
+                    )*
+                }
+            }
+
+            $( #[$fn_attr] )*
+            fn variant_is<T: $crate::_core::any::Any>(&self) -> bool {
+                self.downcast_ref::<T>().is_some()
+            }
+
+            $( #[$fn_attr] )*
+            fn __variant_for_type(&self, type_id: $crate::_core::any::TypeId) -> $crate::_core::option::Option<(&'static str, &'static str)> {
+                $(
+                    if type_id == $crate::_core::any::TypeId::of::<$variant_type>() {
+                        return $crate::_core::option::Option::Some(($label, stringify!($variant_type)));
+                    }
+                )*
+
+                $crate::_core::option::Option::None
+            }
+        }
+    }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_variant_index_of_name {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Look up the index of the variant called `name`.
+            $( #[$fn_attr] )*
+            pub const fn variant_index_of_name(name: &str) -> $crate::_core::option::Option<usize> {
+                $crate::__sum_type_variant_index!($( $name ),*);
+
+                $(
+                    if $crate::__const_str_eq(name, $label) {
+                        return $crate::_core::option::Option::Some(VariantIndex::$name as usize);
+                    }
+                )*
+
+                $crate::_core::option::Option::None
+            }
+
+            /// Look up the index of the variant called `name`, ignoring ASCII
+            /// case.
+            $( #[$fn_attr] )*
+            pub const fn variant_position(name: &str) -> $crate::_core::option::Option<usize> {
+                $crate::__sum_type_variant_index!($( $name ),*);
+
+                $(
+                    if $crate::__const_str_eq_ignore_ascii_case(name, $label) {
+                        return $crate::_core::option::Option::Some(VariantIndex::$name as usize);
+                    }
+                )*
+
+                $crate::_core::option::Option::None
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_variant_table {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Each variant's index and name, in declaration order.
+            pub const VARIANT_TABLE: &'static [(usize, &'static str)] = {
+                $crate::__sum_type_variant_index!($( $name ),*);
+
+                &[
+                    $( (VariantIndex::$name as usize, $label) ),*
+                ]
+            };
+
+            /// The number of variants.
+            pub const VARIANT_COUNT: usize = <[&str]>::len(&[$( $label ),*]);
+
+            /// Call `f` with each variant's index and name, in declaration
+            /// order.
+            $( #[$fn_attr] )*
+            pub fn for_each_variant_name<__F: $crate::_core::ops::FnMut(usize, &'static str)>(mut f: __F) {
+                for &(index, name) in $enum_name::VARIANT_TABLE {
+                    f(index, name);
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_to_variant_index_map {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Create an array with one slot per variant, where only the
+            /// current variant's slot holds `value`.
+            $( #[$fn_attr] )*
+            pub fn to_variant_index_map<__V>(&self, value: __V) -> [$crate::_core::option::Option<__V>; <[&str]>::len(&[$( $label ),*])] {
+                $crate::__sum_type_variant_index!($( $name ),*);
+
+                let index = $crate::SumType::variant_index(self);
+                let mut value = $crate::_core::option::Option::Some(value);
+
+                [
+                    $( if index == VariantIndex::$name as usize { value.take() } else { $crate::_core::option::Option::None } ),*
+                ]
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_hash {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::hash::Hash for $enum_name {
+            $( #[$fn_attr] )*
+            fn hash<H: $crate::_core::hash::Hasher>(&self, state: &mut H) {
+                $crate::_core::hash::Hash::hash(&$crate::SumType::variant_index(self), state);
+
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => $crate::_core::hash::Hash::hash(value, state),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_eq_by_variant_only {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::cmp::PartialEq for $enum_name {
+            $( #[$fn_attr] )*
+            fn eq(&self, other: &$enum_name) -> bool {
+                $crate::SumType::variant_index(self) == $crate::SumType::variant_index(other)
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::cmp::Eq for $enum_name {}
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_ord_by_payload {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $first:ident ($first_label:expr) => $first_type:ty $(, $name:ident ($label:expr) => $variant_type:ty )*) => {
+        const _: () = $crate::_core::assert!(
+            true $( && $crate::__const_type_eq(stringify!($first_type), stringify!($variant_type)) )*,
+            concat!(
+                "The `ord_by_payload` option on `",
+                stringify!($enum_name),
+                "` requires every variant to hold the same type, written the same way"
+            ),
+        );
+
+        const _: () = {
+            // Going through `Any` means mismatched types only trip the
+            // assertion above, rather than a confusing type error in here.
+            fn payload(value: &$enum_name) -> &$first_type {
+                match *value {
+                    $enum_name::$first(ref payload) => payload,
+                    $(
+                        $enum_name::$name(ref payload) => (payload as &dyn $crate::_core::any::Any)
+                            .downcast_ref::<$first_type>()
+                            .expect("every payload has the same type"),
+                    )*
+                }
+            }
+
+            $( #[$impl_attr] )*
+            impl $crate::_core::cmp::PartialEq for $enum_name {
+                $( #[$fn_attr] )*
+                fn eq(&self, other: &$enum_name) -> bool {
+                    payload(self) == payload(other)
+                }
+            }
+
+            $( #[$impl_attr] )*
+            impl $crate::_core::cmp::Eq for $enum_name {}
+
+            $( #[$impl_attr] )*
+            impl $crate::_core::cmp::PartialOrd for $enum_name {
+                $( #[$fn_attr] )*
+                fn partial_cmp(&self, other: &$enum_name) -> $crate::_core::option::Option<$crate::_core::cmp::Ordering> {
+                    $crate::_core::option::Option::Some($crate::_core::cmp::Ord::cmp(self, other))
+                }
+            }
+
+            $( #[$impl_attr] )*
+            impl $crate::_core::cmp::Ord for $enum_name {
+                $( #[$fn_attr] )*
+                fn cmp(&self, other: &$enum_name) -> $crate::_core::cmp::Ordering {
+                    $crate::_core::cmp::Ord::cmp(payload(self), payload(other))
+                }
+            }
+        };
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_payloads_eq {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Compare the payloads of two sum types, ignoring which variants
+            /// hold them. Payloads of different types are never equal.
+            $( #[$fn_attr] )*
+            pub fn payloads_eq(&self, other: &$enum_name) -> bool {
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => {
+                            $crate::SumType::downcast_ref::<$variant_type>(other)
+                                .map_or(false, |other| value == other)
+                        }
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_option_pair {
+    (
+        $enum_name:ident,
+        [$( #[$fn_attr:meta] )*],
+        [$( #[$impl_attr:meta] )*],
+        $first:ident ($first_label:expr) => $first_type:ty,
+        $second:ident ($second_label:expr) => $second_type:ty
+    ) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::From<$enum_name> for ($crate::_core::option::Option<$first_type>, $crate::_core::option::Option<$second_type>) {
+            $( #[$fn_attr] )*
+            fn from(other: $enum_name) -> ($crate::_core::option::Option<$first_type>, $crate::_core::option::Option<$second_type>) {
+                match other {
+                    $enum_name::$first(value) => ($crate::_core::option::Option::Some(value), $crate::_core::option::Option::None),
+                    $enum_name::$second(value) => ($crate::_core::option::Option::None, $crate::_core::option::Option::Some(value)),
+                }
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::TryFrom<($crate::_core::option::Option<$first_type>, $crate::_core::option::Option<$second_type>)> for $enum_name {
+            type Error = ($crate::_core::option::Option<$first_type>, $crate::_core::option::Option<$second_type>);
+
+            $( #[$fn_attr] )*
+            fn try_from(pair: ($crate::_core::option::Option<$first_type>, $crate::_core::option::Option<$second_type>)) -> $crate::_core::result::Result<$enum_name, Self::Error> {
+                match pair {
+                    ($crate::_core::option::Option::Some(value), $crate::_core::option::Option::None) => $crate::_core::result::Result::Ok($enum_name::$first(value)),
+                    ($crate::_core::option::Option::None, $crate::_core::option::Option::Some(value)) => $crate::_core::result::Result::Ok($enum_name::$second(value)),
+                    other => $crate::_core::result::Result::Err(other),
+                }
+            }
+        }
+    };
+    ($enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        compile_error!(concat!(
+            "The `option_pair` option requires `",
+            stringify!($enum_name),
+            "` to have exactly two variants"
+        ));
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_is_unit_variant {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Does the variant at `index` hold a zero-sized payload (e.g.
+            /// `()`), meaning there is no data to encode?
+            $( #[$fn_attr] )*
+            pub const fn is_unit_variant(index: usize) -> bool {
+                $crate::__sum_type_variant_index!($( $name ),*);
+
+                $(
+                    if index == VariantIndex::$name as usize {
+                        return $crate::_core::mem::size_of::<$variant_type>() == 0;
+                    }
+                )*
+
+                false
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_payload_size {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// The size of the current variant's payload, in bytes.
+            $( #[$fn_attr] )*
+            // a reference payload is stored inline, so its own size is what we want
+            #[allow(clippy::size_of_ref)]
+            pub fn payload_size(&self) -> usize {
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => $crate::_core::mem::size_of_val(value),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_c_repr {
+    (@tag [$($vis:tt)*] $c_repr:ident, $enum_name:ident, [$( #[$impl_attr:meta] )*], [$( $done:ident ($done_type:ty) = $tag:expr ),*], [$next:expr], $name:ident ($variant_type:ty) $(, $rest:ident ($rest_type:ty) )*) => {
+        $crate::__sum_type_c_repr!(@tag [$($vis)*] $c_repr, $enum_name, [$( #[$impl_attr] )*], [$( $done ($done_type) = $tag, )* $name ($variant_type) = $next], [$next + 1], $( $rest ($rest_type) ),*);
+    };
+    (@tag [$($vis:tt)*] $c_repr:ident, $enum_name:ident, [$( #[$impl_attr:meta] )*], [$( $done:ident ($done_type:ty) = $tag:expr ),*], [$next:expr], ) => {
+        /// A `#[repr(C, i32)]` copy of
+        #[doc = concat!("`", stringify!($enum_name), "`,")]
+        /// laid out like a C tagged union whose tag is the variant's index.
+        /// It is only FFI-safe when every payload is.
+        #[repr(C, i32)]
+        #[allow(non_camel_case_types)]
+        $( #[$impl_attr] )*
+        $($vis)* enum $c_repr {
+            $( $done($done_type) = $tag, )*
+        }
+    };
+    ([$($vis:tt)*] $c_repr:ident, $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $crate::__sum_type_c_repr!(@tag [$($vis)*] $c_repr, $enum_name, [$( #[$impl_attr] )*], [], [0], $( $name ($variant_type) ),*);
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::From<$enum_name> for $c_repr {
+            $( #[$fn_attr] )*
+            fn from(other: $enum_name) -> $c_repr {
+                match other {
+                    $( $enum_name::$name(value) => $c_repr::$name(value), )*
+                }
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::From<$c_repr> for $enum_name {
+            $( #[$fn_attr] )*
+            fn from(other: $c_repr) -> $enum_name {
+                match other {
+                    $( $c_repr::$name(value) => $enum_name::$name(value), )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_truthy {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], [$( $truthy:ident ),*]) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Is this one of the variants marked as truthy?
+            $( #[$fn_attr] )*
+            pub fn is_ok_like(&self) -> bool {
+                match *self {
+                    $( $enum_name::$truthy(_) )|* => true,
+                    #[allow(unreachable_patterns)]
+                    _ => false,
+                }
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl<'a> $crate::_core::ops::Not for &'a $enum_name {
+            type Output = bool;
+
+            /// Is this *not* one of the variants marked as truthy?
+            $( #[$fn_attr] )*
+            fn not(self) -> bool {
+                !self.is_ok_like()
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::ops::Not for $enum_name {
+            type Output = bool;
+
+            /// Is this *not* one of the variants marked as truthy?
+            $( #[$fn_attr] )*
+            fn not(self) -> bool {
+                !self.is_ok_like()
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_fmt_delegate {
+    ($fmt_trait:ident, $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::fmt::$fmt_trait for $enum_name {
+            $( #[$fn_attr] )*
+            fn fmt(&self, f: &mut $crate::_core::fmt::Formatter) -> $crate::_core::fmt::Result {
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => $crate::_core::fmt::$fmt_trait::fmt(value, f),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_debug_indexed {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::fmt::Debug for $enum_name {
+            $( #[$fn_attr] )*
+            fn fmt(&self, f: &mut $crate::_core::fmt::Formatter) -> $crate::_core::fmt::Result {
+                write!(f, "{}#{}(", $crate::SumType::variant(self), $crate::SumType::variant_index(self))?;
+
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => $crate::_core::fmt::Debug::fmt(value, f)?,
+                    )*
+                }
+
+                f.write_str(")")
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_as_ref_any {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::AsRef<dyn $crate::_core::any::Any> for $enum_name
+        where
+            $( $variant_type: 'static ),*
+        {
+            $( #[$fn_attr] )*
+            fn as_ref(&self) -> &(dyn $crate::_core::any::Any + 'static) {
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => value,
+                    )*
+                }
+            }
+        }
+
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_as_any {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name
+        where
+            $( $variant_type: 'static ),*
+        {
+            /// The active payload, as a `&dyn Any`.
+            $( #[$fn_attr] )*
+            pub fn active_as_any(&self) -> &(dyn $crate::_core::any::Any + 'static) {
+                $crate::_core::convert::AsRef::as_ref(self)
+            }
+
+            /// An iterator which yields the active payload exactly once.
+            $( #[$fn_attr] )*
+            pub fn variant_ref_any_iter(&self) -> impl $crate::_core::iter::Iterator<Item = &(dyn $crate::_core::any::Any + 'static)> {
+                $crate::_core::iter::once(self.active_as_any())
+            }
+        }
+    };
+}
+
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_into_parts {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Split this into the variant's index and its type-erased
+            /// payload.
+            $( #[$fn_attr] )*
+            pub fn into_parts(self) -> (usize, $crate::_alloc::boxed::Box<dyn $crate::_core::any::Any>) {
+                let index = $crate::SumType::variant_index(&self);
+
+                match self {
+                    $(
+                        $enum_name::$name(value) => (index, $crate::_alloc::boxed::Box::new(value)),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_into_parts {
+    ($enum_name:ident, $($rest:tt)*) => {
+        compile_error!(concat!(
+            "The `into_parts` option on `",
+            stringify!($enum_name),
+            "` requires the `alloc` feature of `sum_type`"
+        ));
+    };
+}
+
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_payload_if_index {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Get the type-erased payload, as long as this is the variant with
+            /// the given index.
+            $( #[$fn_attr] )*
+            pub fn payload_if_index(self, index: usize) -> $crate::_core::option::Option<$crate::_alloc::boxed::Box<dyn $crate::_core::any::Any>> {
+                if $crate::SumType::variant_index(&self) != index {
+                    return $crate::_core::option::Option::None;
+                }
+
+                match self {
+                    $(
+                        $enum_name::$name(value) => $crate::_core::option::Option::Some($crate::_alloc::boxed::Box::new(value)),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_payload_if_index {
+    ($enum_name:ident, $($rest:tt)*) => {
+        compile_error!(concat!(
+            "The `tryfrom_by_index` option on `",
+            stringify!($enum_name),
+            "` requires the `alloc` feature of `sum_type`"
+        ));
+    };
+}
+
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_into_iter {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::iter::IntoIterator for $enum_name {
+            type Item = $crate::_alloc::boxed::Box<dyn $crate::_core::any::Any>;
+            type IntoIter = $crate::_core::iter::Once<$crate::_alloc::boxed::Box<dyn $crate::_core::any::Any>>;
+
+            $( #[$fn_attr] )*
+            fn into_iter(self) -> Self::IntoIter {
+                let payload: $crate::_alloc::boxed::Box<dyn $crate::_core::any::Any> = match self {
+                    $(
+                        $enum_name::$name(value) => $crate::_alloc::boxed::Box::new(value),
+                    )*
+                };
+
+                $crate::_core::iter::once(payload)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "alloc"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_into_iter {
+    ($enum_name:ident, $($rest:tt)*) => {
+        compile_error!(concat!(
+            "The `into_iter` option on `",
+            stringify!($enum_name),
+            "` requires the `alloc` feature of `sum_type`"
+        ));
+    };
+}
+
+// Collect the variants tagged with `bytes`, skipping any variants without
+// options in one go.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_bytes {
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, [], [$( { [] $skip:ident } )*]) => {};
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, [$($found:ident)+], [$( { [] $skip:ident } )*]) => {
+        $crate::__sum_type_bytes!($enum_name, $fn_attrs, $impl_attrs, $($found),+);
+    };
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, [$($found:ident)*],
+        [$( { [] $skip:ident } )* { [(bytes) $($opts:tt)*] $name:ident } $($rest:tt)*]) => {
+        $crate::__sum_type_bytes!(@scan $enum_name, $fn_attrs, $impl_attrs, [$($found)* $name], [$($rest)*]);
+    };
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $found:tt,
+        [$( { [] $skip:ident } )* { [$other:tt $($opts:tt)*] $name:ident } $($rest:tt)*]) => {
+        $crate::__sum_type_bytes!(@scan $enum_name, $fn_attrs, $impl_attrs, $found, [{ [$($opts)*] $name } $($rest)*]);
+    };
+
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ),+) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Borrow the payload as bytes, if this is one of the byte-like
+            /// variants.
+            $( #[$fn_attr] )*
+            pub fn as_bytes(&self) -> $crate::_core::option::Option<&[u8]> {
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => $crate::_core::option::Option::Some($crate::_core::convert::AsRef::<[u8]>::as_ref(value)),
+                    )+
+                    #[allow(unreachable_patterns)]
+                    _ => $crate::_core::option::Option::None,
+                }
+            }
+        }
+    };
+}
+
+// Look through every variant's options for the `ok` and `err` tags. Variants
+// without any options are skipped in one go, so this takes a step per option
+// rather than per variant.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_result {
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, [] [], [$( { [] $skip:ident $skip_ty:tt } )*]) => {};
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, [$ok:ident $ok_type:tt] [$err:ident $err_type:tt], [$( { [] $skip:ident $skip_ty:tt } )*]) => {
+        $crate::__sum_type_result!($enum_name, $fn_attrs, $impl_attrs, $ok $ok_type, $err $err_type);
+    };
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $ok:tt $err:tt, [$( { [] $skip:ident $skip_ty:tt } )*]) => {
+        compile_error!(concat!(
+            "The `",
+            stringify!($enum_name),
+            "` type needs both an `#[sum_type(ok)]` variant and an `#[sum_type(err)]` variant"
+        ));
+    };
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, [] $err:tt,
+        [$( { [] $skip:ident $skip_ty:tt } )* { [(ok) $($opts:tt)*] $name:ident $ty:tt } $($rest:tt)*]) => {
+        $crate::__sum_type_result!(@scan $enum_name, $fn_attrs, $impl_attrs, [$name $ty] $err, [{ [$($opts)*] $name $ty } $($rest)*]);
+    };
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $ok:tt [],
+        [$( { [] $skip:ident $skip_ty:tt } )* { [(err) $($opts:tt)*] $name:ident $ty:tt } $($rest:tt)*]) => {
+        $crate::__sum_type_result!(@scan $enum_name, $fn_attrs, $impl_attrs, $ok [$name $ty], [{ [$($opts)*] $name $ty } $($rest)*]);
+    };
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $ok:tt $err:tt,
+        [$( { [] $skip:ident $skip_ty:tt } )* { [(ok) $($opts:tt)*] $name:ident $ty:tt } $($rest:tt)*]) => {
+        compile_error!(concat!(
+            "The `",
+            stringify!($enum_name),
+            "` type has more than one `#[sum_type(ok)]` variant"
+        ));
+    };
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $ok:tt $err:tt,
+        [$( { [] $skip:ident $skip_ty:tt } )* { [(err) $($opts:tt)*] $name:ident $ty:tt } $($rest:tt)*]) => {
+        compile_error!(concat!(
+            "The `",
+            stringify!($enum_name),
+            "` type has more than one `#[sum_type(err)]` variant"
+        ));
+    };
+    (@scan $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $ok:tt $err:tt,
+        [$( { [] $skip:ident $skip_ty:tt } )* { [$other:tt $($opts:tt)*] $name:ident $ty:tt } $($rest:tt)*]) => {
+        $crate::__sum_type_result!(@scan $enum_name, $fn_attrs, $impl_attrs, $ok $err, [{ [$($opts)*] $name $ty } $($rest)*]);
+    };
+
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $ok:ident ($ok_type:ty), $err:ident ($err_type:ty)) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Convert into a `Result`, so the `?` operator can be used.
+            $( #[$fn_attr] )*
+            pub fn into_result(self) -> $crate::_core::result::Result<$ok_type, $err_type> {
+                match self {
+                    $enum_name::$ok(value) => $crate::_core::result::Result::Ok(value),
+                    $enum_name::$err(error) => $crate::_core::result::Result::Err(error),
+                }
+            }
+
+            /// Convert from a `Result`.
+            $( #[$fn_attr] )*
+            pub fn from_result(result: $crate::_core::result::Result<$ok_type, $err_type>) -> $enum_name {
+                match result {
+                    $crate::_core::result::Result::Ok(value) => $enum_name::$ok(value),
+                    $crate::_core::result::Result::Err(error) => $enum_name::$err(error),
+                }
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::From<$crate::_core::result::Result<$ok_type, $err_type>> for $enum_name {
+            $( #[$fn_attr] )*
+            fn from(result: $crate::_core::result::Result<$ok_type, $err_type>) -> $enum_name {
+                $enum_name::from_result(result)
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::From<$enum_name> for $crate::_core::result::Result<$ok_type, $err_type> {
+            $( #[$fn_attr] )*
+            fn from(other: $enum_name) -> $crate::_core::result::Result<$ok_type, $err_type> {
+                other.into_result()
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_collapse {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Convert the payload into a single common type, for when every
+            /// variant's type implements `Into<Target>`.
+            $( #[$fn_attr] )*
+            pub fn collapse<__Target>(self) -> __Target
+            where
+                $( $variant_type: $crate::_core::convert::Into<__Target>, )*
+            {
+                match self {
+                    $(
+                        $enum_name::$name(value) => $crate::_core::convert::Into::into(value),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_fold_ref {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Borrow the payload and pass it to the function for the current
+            /// variant. The functions are given in declaration order.
+            #[allow(non_snake_case, clippy::too_many_arguments)]
+            $( #[$fn_attr] )*
+            pub fn fold_ref<__R>(&self, $( $name: impl $crate::_core::ops::FnOnce(&$variant_type) -> __R ),*) -> __R {
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => $name(value),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_variant_meta {
+    (@find [(variants_meta) $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_variant_meta!($($args)*);
+    };
+    (@find [$other:tt $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_variant_meta!(@find [$($rest)*], $($args)*);
+    };
+    (@find [], $($args:tt)*) => {};
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], [$( $name:ident ($label:expr) $docs:tt => $variant_type:ty ),*]) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Everything known about each variant, in declaration order.
+            pub const VARIANTS_META: &'static [$crate::VariantInfo] = {
+                $crate::__sum_type_variant_index!($( $name ),*);
+
+                &[
+                    $(
+                        $crate::VariantInfo {
+                            name: $label,
+                            type_name: stringify!($variant_type),
+                            index: VariantIndex::$name as usize,
+                            doc: $crate::__sum_type_variant_meta!(@doc $docs),
+                            __non_exhaustive: (),
+                        }
+                    ),*
+                ]
+            };
+
+            /// The `VARIANTS_META` table, for callers which prefer a function.
+            $( #[$fn_attr] )*
+            pub fn variant_metadata() -> &'static [$crate::VariantInfo] {
+                $enum_name::VARIANTS_META
+            }
+
+            /// The current variant's doc comment, if it has one, tidied up
+            /// the same way as in `VARIANTS_META`.
+            $( #[$fn_attr] )*
+            pub fn variant_doc(&self) -> $crate::_core::option::Option<&'static str> {
+                $enum_name::VARIANTS_META[$crate::SumType::variant_index(self)].doc
+            }
+
+            /// Each variant's name and doc comment, in declaration order. The
+            /// docs are tidied up the same way as in `VARIANTS_META`.
+            $( #[$fn_attr] )*
+            pub fn variants_doc_table() -> impl $crate::_core::iter::Iterator<Item = (&'static str, $crate::_core::option::Option<&'static str>)> {
+                $enum_name::VARIANTS_META.iter().map(|info| (info.name, info.doc))
+            }
+        }
+    };
+    (@doc []) => { $crate::_core::option::Option::None };
+    (@doc [#[doc = $first:expr] $( #[doc = $rest:expr] )*]) => {
+        $crate::_core::option::Option::Some({
+            const RAW: &str = concat!($first $(, "\n", $rest)*);
+            const NORMALIZED: [u8; $crate::__doc_len(RAW)] = $crate::__doc_normalize(RAW);
+
+            match $crate::_core::str::from_utf8(&NORMALIZED) {
+                $crate::_core::result::Result::Ok(doc) => doc,
+                $crate::_core::result::Result::Err(_) => $crate::_core::panic!("only ASCII whitespace is removed from doc comments"),
+            }
+        })
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_retag {
+    ($enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $crate::__sum_type_retag!(@impl $enum_name, $fn_attrs, $impl_attrs, [$( $name ),*], $( $name ($label) => $variant_type ),*);
+    };
+    (@impl $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $names:tt, $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Move the payload into the variant called `name`, as long as
+            /// that variant holds the same type.
+            $( #[$fn_attr] )*
+            pub fn retag(self, name: &str) -> $crate::_core::result::Result<$enum_name, $crate::RetagError> {
+                let actual_variant = $crate::SumType::variant(&self);
+                let all_variants = $crate::SumType::variants(&self);
+
+                $(
+                    $crate::__sum_type_retag!(@target self, name, actual_variant, all_variants,
+                        $enum_name::$name ($label) => $variant_type,
+                        $names);
+                )*
+
+                $crate::_core::result::Result::Err($crate::RetagError::UnknownVariant($crate::ParseError {
+                    variant: $crate::_core::option::Option::None,
+                    all_variants,
+                    __non_exhaustive: (),
+                }))
+            }
+        }
+    };
+    (
+        @target $this:ident, $target_name:ident, $actual_variant:ident, $all_variants:ident,
+        $enum_name:ident::$target:ident ($label:expr) => $target_type:ty,
+        [$( $name:ident ),*]
+    ) => {
+        if $target_name == $label {
+            let retagged = match $this {
+                $(
+                    $enum_name::$name(value) => {
+                        let mut slot = $crate::_core::option::Option::Some(value);
+                        (&mut slot as &mut dyn $crate::_core::any::Any)
+                            .downcast_mut::<$crate::_core::option::Option<$target_type>>()
+                            .and_then($crate::_core::option::Option::take)
+                            .map($enum_name::$target)
+                    }
+                )*
+            };
+
+            return retagged.ok_or($crate::RetagError::WrongType($crate::InvalidType {
+                expected_variant: $label,
+                expected_type: stringify!($target_type),
+                actual_variant: $actual_variant,
+                all_variants: $all_variants,
+                __non_exhaustive: (),
+            }));
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_multiple_variants {
+    ($enum_name:ident, $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        // checked after any `#[cfg]`-gated variants have been dropped
+        const _: () = $crate::_core::assert!(
+            <[&str]>::len(&[$( stringify!($name) ),*]) >= 2,
+            concat!("The `", stringify!($enum_name), "` type must have more than one variant"),
+        );
+    };
+}
+
+// Payloads are stored inline, so catch the common unsized types before they
+// turn into a pile of trait-bound errors.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_unsized {
+    (@check $enum_name:ident, $name:ident (str)) => {
+        $crate::__sum_type_unsized!($enum_name, $name, [str], "`&'static str` or a `Box<str>`");
+    };
+    (@check $enum_name:ident, $name:ident ([$elem:ty])) => {
+        $crate::__sum_type_unsized!($enum_name, $name, [[$elem]],
+            concat!("`&'static [", stringify!($elem), "]` or a `Box<[", stringify!($elem), "]>`"));
+    };
+    (@check $enum_name:ident, $name:ident (dyn $($bounds:tt)+)) => {
+        $crate::__sum_type_unsized!($enum_name, $name, [dyn $($bounds)+],
+            concat!("a `Box<", stringify!(dyn $($bounds)+), ">`"));
+    };
+    (@check $enum_name:ident, $name:ident $($payload:tt)*) => {};
+    ($enum_name:ident, $name:ident, [$($payload:tt)+], $suggestion:expr) => {
+        compile_error!(concat!(
+            "The `",
+            stringify!($name),
+            "` variant of `",
+            stringify!($enum_name),
+            "` can't hold a `",
+            stringify!($($payload)+),
+            "` directly because it is unsized, use something like ",
+            $suggestion,
+            " instead"
+        ));
+    };
+}
+
+// Payloads are stored inline, so an unsized payload (e.g. `Path` instead of
+// `&Path`) can never work. The parser catches `str`, slices and trait objects
+// itself, this is a fallback for everything else. Naming the bound after the fix makes rustc's
+// trait-bound error point people in the right direction.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __assert_sized_variants {
+    ($enum_name:ident, $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        #[allow(dead_code)]
+        const _: () = {
+            fn payload_must_be_sized_use_a_reference_or_box<T: $crate::_core::marker::Sized>() {}
+
+            fn assert_sized() {
+                $( payload_must_be_sized_use_a_reference_or_box::<$variant_type>(); )*
+            }
+        };
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_try_fold {
+    ($method:ident, $op_trait:ident, $op:tt, $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Combine an iterator of values which all hold the same variant.
+            /// An empty iterator is an error, because there's no variant to
+            /// take the identity from.
+            $( #[$fn_attr] )*
+            pub fn $method<I>(items: I) -> $crate::_core::result::Result<$enum_name, $crate::CombineError>
+            where
+                I: $crate::_core::iter::IntoIterator<Item = $enum_name>,
+                $( $variant_type: $crate::_core::ops::$op_trait<Output = $variant_type>, )*
+            {
+                let mut items = items.into_iter();
+                let mut total = match items.next() {
+                    $crate::_core::option::Option::Some(first) => first,
+                    $crate::_core::option::Option::None => return $crate::_core::result::Result::Err($crate::CombineError::Empty),
+                };
+
+                for item in items {
+                    total = match (total, item) {
+                        $(
+                            ($enum_name::$name(left), $enum_name::$name(right)) => $enum_name::$name(left $op right),
+                        )*
+                        (total, item) => {
+                            let expected_type = match total {
+                                $( $enum_name::$name(_) => stringify!($variant_type), )*
+                            };
+
+                            return $crate::_core::result::Result::Err($crate::CombineError::MixedVariants($crate::InvalidType {
+                                expected_variant: $crate::SumType::variant(&total),
+                                expected_type,
+                                actual_variant: $crate::SumType::variant(&item),
+                                all_variants: $crate::SumType::variants(&total),
+                                __non_exhaustive: (),
+                            }));
+                        }
+                    };
+                }
+
+                $crate::_core::result::Result::Ok(total)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_neg {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::ops::Neg for $enum_name {
+            type Output = $enum_name;
+
+            $( #[$fn_attr] )*
+            fn neg(self) -> $enum_name {
+                match self {
+                    $(
+                        $enum_name::$name(value) => $enum_name::$name($crate::_core::ops::Neg::neg(value)),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_try_assign {
+    ($method:ident, $op_trait:ident, $op:tt, $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Combine another value's payload into this one in place, as
+            /// long as both hold the same variant.
+            $( #[$fn_attr] )*
+            pub fn $method(&mut self, other: $enum_name) -> $crate::_core::result::Result<(), $crate::InvalidType>
+            where
+                $( $variant_type: $crate::_core::ops::$op_trait, )*
+            {
+                match (self, other) {
+                    $(
+                        (&mut $enum_name::$name(ref mut left), $enum_name::$name(right)) => {
+                            *left $op right;
+                            $crate::_core::result::Result::Ok(())
+                        }
+                    )*
+                    (this, other) => {
+                        let expected_type = match *this {
+                            $( $enum_name::$name(_) => stringify!($variant_type), )*
+                        };
+
+                        $crate::_core::result::Result::Err($crate::InvalidType {
+                            expected_variant: $crate::SumType::variant(&*this),
+                            expected_type,
+                            actual_variant: $crate::SumType::variant(&other),
+                            all_variants: $crate::SumType::variants(&other),
+                            __non_exhaustive: (),
+                        })
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_copy_accessor {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $method:ident, $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Get a copy of the payload if this is the corresponding variant.
+            $( #[$fn_attr] )*
+            pub fn $method(&self) -> $crate::_core::option::Option<$variant_type>
+            where
+                $variant_type: $crate::_core::marker::Copy,
+            {
+                match *self {
+                    $enum_name::$name(value) => $crate::_core::option::Option::Some(value),
+                    #[allow(unreachable_patterns)]
+                    _ => $crate::_core::option::Option::None,
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_variant_key {
+    ([$($vis:tt)*] $key:ident, $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*]) => {
+        /// A wrapper which compares and hashes a sum type by its variant's
+        /// name alone, so it can be looked up in maps with a `&str`.
+        $( #[$impl_attr] )*
+        $($vis)* struct $key(pub $enum_name);
+
+        // implemented by hand so the sum type doesn't need to be Debug
+        $( #[$impl_attr] )*
+        impl $crate::_core::fmt::Debug for $key {
+            $( #[$fn_attr] )*
+            fn fmt(&self, f: &mut $crate::_core::fmt::Formatter) -> $crate::_core::fmt::Result {
+                f.debug_tuple(stringify!($key))
+                    .field(&$crate::SumType::variant(&self.0))
+                    .finish()
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::cmp::PartialEq for $key {
+            $( #[$fn_attr] )*
+            fn eq(&self, other: &$key) -> bool {
+                $crate::SumType::variant(&self.0) == $crate::SumType::variant(&other.0)
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::cmp::Eq for $key {}
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::cmp::PartialOrd for $key {
+            $( #[$fn_attr] )*
+            fn partial_cmp(&self, other: &$key) -> $crate::_core::option::Option<$crate::_core::cmp::Ordering> {
+                $crate::_core::option::Option::Some($crate::_core::cmp::Ord::cmp(self, other))
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::cmp::Ord for $key {
+            $( #[$fn_attr] )*
+            fn cmp(&self, other: &$key) -> $crate::_core::cmp::Ordering {
+                $crate::_core::cmp::Ord::cmp($crate::SumType::variant(&self.0), $crate::SumType::variant(&other.0))
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::hash::Hash for $key {
+            $( #[$fn_attr] )*
+            fn hash<H: $crate::_core::hash::Hasher>(&self, state: &mut H) {
+                $crate::_core::hash::Hash::hash($crate::SumType::variant(&self.0), state);
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::borrow::Borrow<str> for $key {
+            $( #[$fn_attr] )*
+            fn borrow(&self) -> &str {
+                $crate::SumType::variant(&self.0)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_builder {
+    ([$($vis:tt)*] $builder:ident, $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*]) => {
+        /// A builder which keeps whichever variant was set last.
+        $( #[$impl_attr] )*
+        #[derive(Default)]
+        $($vis)* struct $builder {
+            value: $crate::_core::option::Option<$enum_name>,
+        }
+
+        $( #[$impl_attr] )*
+        impl $builder {
+            /// Create a new builder without any variant set.
+            $( #[$fn_attr] )*
+            pub fn new() -> $builder {
+                $builder { value: $crate::_core::option::Option::None }
+            }
+
+            /// Get the most recently set variant, if there was one.
+            $( #[$fn_attr] )*
+            pub fn build(self) -> $crate::_core::option::Option<$enum_name> {
+                self.value
+            }
+        }
+    };
+    (@setter [(builder = $builder:ident) $($rest:tt)*], $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $setter:ident, $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl $builder {
+            /// Use this variant as the builder's value.
+            $( #[$fn_attr] )*
+            pub fn $setter(mut self, value: $variant_type) -> $builder {
+                self.value = $crate::_core::option::Option::Some($enum_name::$name(value));
+                self
+            }
+        }
+    };
+    (@setter [$other:tt $($rest:tt)*], $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $setter:ident, $name:ident ($label:expr) => $variant_type:ty) => {
+        $crate::__sum_type_builder!(@setter [$($rest)*], $enum_name, $fn_attrs, $impl_attrs, $setter, $name ($label) => $variant_type);
+    };
+    (@setter [], $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $setter:ident, $name:ident ($label:expr) => $variant_type:ty) => {
+        compile_error!(concat!(
+            "The `",
+            stringify!($enum_name),
+            "::",
+            stringify!($name),
+            "` variant's builder setter requires `#[sum_type(builder = ...)]` on the enum"
+        ));
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_match_helper {
+    // `$d` is a literal `$`, which has to be passed in last because a `$`
+    // followed by anything but a closing delimiter is read as a metavariable
+    ($helper:ident, $enum_name:ident, $d:tt) => {
+        #[allow(unused_macros)]
+        macro_rules! $helper {
+            ($d value:expr; $d( $d variant:ident ($d bind:pat) => $d body:expr ),* $d(,)?) => {
+                match $d value {
+                    $d(
+                        $enum_name::$d variant($d bind) => $d body,
+                    )*
+                }
+            };
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_from_str {
+    ($compare:ident, $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::str::FromStr for $enum_name {
+            type Err = $crate::ParseError;
+
+            $( #[$fn_attr] )*
+            fn from_str(s: &str) -> $crate::_core::result::Result<$enum_name, Self::Err> {
+                const VARIANTS: &[&str] = &[ $( $label ),* ];
+
+                let s = s.trim();
+                let (name, payload) = match s.find(char::is_whitespace) {
+                    $crate::_core::option::Option::Some(index) => (&s[..index], s[index..].trim_start()),
+                    $crate::_core::option::Option::None => (s, ""),
+                };
+
+                $(
+                    if name.$compare($label) {
+                        return payload
+                            .parse::<$variant_type>()
+                            .map($enum_name::$name)
+                            .map_err(|_| $crate::ParseError {
+                                variant: $crate::_core::option::Option::Some($label),
+                                all_variants: VARIANTS,
+                                __non_exhaustive: (),
+                            });
+                    }
+                )*
+
+                $crate::_core::result::Result::Err($crate::ParseError {
+                    variant: $crate::_core::option::Option::None,
+                    all_variants: VARIANTS,
+                    __non_exhaustive: (),
+                })
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_partial_eq_str {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*]) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::cmp::PartialEq<str> for $enum_name {
+            $( #[$fn_attr] )*
+            fn eq(&self, other: &str) -> bool {
+                $crate::SumType::variant(self) == other
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl<'a> $crate::_core::cmp::PartialEq<&'a str> for $enum_name {
+            $( #[$fn_attr] )*
+            fn eq(&self, other: &&'a str) -> bool {
+                $crate::SumType::variant(self) == *other
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_partial_eq_option {
+    (@find [(partial_eq_option) $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_partial_eq_option!($($args)*);
+    };
+    (@find [$other:tt $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_partial_eq_option!(@find [$($rest)*], $($args)*);
+    };
+    (@find [], $($args:tt)*) => {};
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::cmp::PartialEq<$crate::_core::option::Option<$variant_type>> for $enum_name {
+            $( #[$fn_attr] )*
+            fn eq(&self, other: &$crate::_core::option::Option<$variant_type>) -> bool {
+                match (self, other) {
+                    (&$enum_name::$name(ref value), &$crate::_core::option::Option::Some(ref other)) => value == other,
+                    #[allow(unreachable_patterns)]
+                    _ => false,
+                }
+            }
+        }
+    };
+}
+
+/// The name reported for a variant, taking `#[sum_type(rename = "...")]`
+/// into account.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_label {
+    ($name:ident [(rename = $label:expr) $($rest:tt)*]) => {
+        $label
+    };
+    ($name:ident [$other:tt $($rest:tt)*]) => {
+        $crate::__sum_type_label!($name [$($rest)*])
+    };
+    ($name:ident []) => {
+        stringify!($name)
+    };
+}
+
+/// The markdown list of variants which the `doc_variants` option appends
+/// to an enum's documentation.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_doc_variants {
+    ($( $label:expr => $variant_type:ty ),*) => {
+        concat!(
+            "This sum type holds one of:\n\n",
+            $( "- `", $label, "`: `", stringify!($variant_type), "`\n", )*
+        )
+    };
+}
+
+/// Declare a fieldless `VariantIndex` enum with one variant per sum type
+/// variant, so `VariantIndex::Name as usize` gives the variant's index.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_variant_index {
+    ($( $name:ident ),*) => {
+        #[allow(non_camel_case_types, dead_code)]
+        enum VariantIndex {
+            $( $name ),*
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_from_index_default {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Create the variant at `index` using a default payload, or
+            /// `None` if the index is out of range.
+            $( #[$fn_attr] )*
+            pub fn from_index_default(index: usize) -> $crate::_core::option::Option<$enum_name>
+            where
+                $( $variant_type: $crate::_core::default::Default, )*
+            {
+                $crate::__sum_type_variant_index!($( $name ),*);
+
+                $(
+                    if index == VariantIndex::$name as usize {
+                        return $crate::_core::option::Option::Some($enum_name::$name($crate::_core::default::Default::default()));
+                    }
+                )*
+
+                $crate::_core::option::Option::None
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_reset_payload {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Keep the current variant, but replace its payload with the
+            /// default value.
+            $( #[$fn_attr] )*
+            pub fn reset_payload(self) -> $enum_name
+            where
+                $( $variant_type: $crate::_core::default::Default, )*
+            {
+                match self {
+                    $(
+                        $enum_name::$name(_) => $enum_name::$name($crate::_core::default::Default::default()),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_from_ref {
+    ($enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( $crate::__sum_type_from_ref!(@variant $enum_name, $fn_attrs, $impl_attrs, $name ($label) => $variant_type); )*
+    };
+    (@variant $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl<'a> $crate::_core::convert::From<&'a $variant_type> for $enum_name {
+            $( #[$fn_attr] )*
+            fn from(other: &'a $variant_type) -> $enum_name {
+                $enum_name::$name($crate::_core::clone::Clone::clone(other))
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_optional {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Has the current variant's payload been filled in?
+            $( #[$fn_attr] )*
+            pub fn is_set(&self) -> bool {
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => value.is_some(),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_try_from_ref_clone {
+    ($enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( $crate::__sum_type_try_from_ref_clone!(@variant $enum_name, $fn_attrs, $impl_attrs, $name ($label) => $variant_type); )*
+    };
+    (@variant $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl<'a> $crate::_core::convert::TryFrom<&'a $enum_name> for $variant_type {
+            type Error = $crate::InvalidType;
+
+            $( #[$fn_attr] )*
+            fn try_from(other: &'a $enum_name) -> $crate::_core::result::Result<$variant_type, Self::Error> {
+                if let $enum_name::$name(ref value) = *other {
+                    $crate::_core::result::Result::Ok($crate::_core::clone::Clone::clone(value))
+                } else {
+                    $crate::_core::result::Result::Err($crate::InvalidType {
+                        expected_variant: $label,
+                        expected_type: stringify!($variant_type),
+                        actual_variant: $crate::SumType::variant(other),
+                        all_variants: $crate::SumType::variants(other),
+                        __non_exhaustive: (),
+                    })
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_flatten {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $leaf_type:ty, $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::From<$leaf_type> for $enum_name {
+            $( #[$fn_attr] )*
+            fn from(other: $leaf_type) -> $enum_name {
+                $enum_name::$name(<$variant_type as $crate::_core::convert::From<$leaf_type>>::from(other))
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_as_debug {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Get the payload as a `Debug` trait object.
+            $( #[$fn_attr] )*
+            pub fn as_debug(&self) -> &dyn $crate::_core::fmt::Debug {
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => value,
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_kind {
+    (@find [(kind = $kind:ident) $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_kind!($kind, $($args)*);
+    };
+    (@find [$other:tt $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_kind!(@find [$($rest)*], $($args)*);
+    };
+    (@find [], $opts:tt, $vis:tt, $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident $docs:tt ($label:expr) [] ),*]) => {};
+    // discriminants only end up on the kind enum, so don't silently drop them
+    (@find [], $opts:tt, $vis:tt, $enum_name:ident, $($rest:tt)*) => {
+        compile_error!(concat!(
+            "Variants of `",
+            stringify!($enum_name),
+            "` can only have explicit discriminants when a kind is generated with #[sum_type(kind = ...)]"
+        ));
+    };
+    (@default $kind:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $first:ident $(, $rest:ident)*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::default::Default for $kind {
+            /// The first variant's kind.
+            $( #[$fn_attr] )*
+            fn default() -> $kind {
+                $kind::$first
+            }
+        }
+    };
+    ($kind:ident, $opts:tt, [$($vis:tt)*], $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], [$( $name:ident [$( #[$doc:meta] )*] ($label:expr) [$( = $discr:expr )?] ),*]) => {
+        /// The kind of variant a sum type holds, without its payload.
+        #[allow(non_camel_case_types)]
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        $($vis)* enum $kind {
+            $(
+                $( #[$doc] )*
+                $name $( = $discr )?,
+            )*
+        }
+
+        $( #[$impl_attr] )*
+        impl $kind {
+            /// The index of this kind, in declaration order.
+            $( #[$fn_attr] )*
+            pub const fn index(&self) -> usize {
+                $crate::__sum_type_variant_index!($( $name ),*);
+
+                match *self {
+                    $(
+                        $kind::$name => VariantIndex::$name as usize,
+                    )*
+                }
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl<'a> $crate::_core::convert::TryFrom<&'a str> for $kind {
+            type Error = $crate::ParseError;
+
+            $( #[$fn_attr] )*
+            fn try_from(name: &'a str) -> $crate::_core::result::Result<$kind, Self::Error> {
+                $(
+                    if name == $label {
+                        return $crate::_core::result::Result::Ok($kind::$name);
+                    }
+                )*
+
+                $crate::_core::result::Result::Err($crate::ParseError {
+                    variant: $crate::_core::option::Option::None,
+                    all_variants: &[ $( $label ),* ],
+                    __non_exhaustive: (),
+                })
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::str::FromStr for $kind {
+            type Err = $crate::ParseError;
+
+            $( #[$fn_attr] )*
+            fn from_str(name: &str) -> $crate::_core::result::Result<$kind, Self::Err> {
+                $crate::_core::convert::TryFrom::try_from(name)
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Which kind of variant this is.
+            $( #[$fn_attr] )*
+            pub const fn kind(&self) -> $kind {
+                match *self {
+                    $(
+                        $enum_name::$name(_) => $kind::$name,
+                    )*
+                }
+            }
+        }
+
+        $crate::__sum_type_kind!(@default $kind, [$( #[$fn_attr] )*], [$( #[$impl_attr] )*], $( $name ),*);
+        $crate::__sum_type_kind_from_primitive!(@find $opts, $kind, [$( #[$fn_attr] )*], [$( #[$impl_attr] )*], [$( $name ),*]);
+        $crate::__sum_type_kind_set!(@find $opts, $kind, $enum_name, [$($vis)*], [$( #[$fn_attr] )*], [$( #[$impl_attr] )*], [$( $name ),*]);
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_kind_from_primitive {
+    (@find [(repr_kind_from_primitive) $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_kind_from_primitive!($($args)*);
+    };
+    (@find [$other:tt $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_kind_from_primitive!(@find [$($rest)*], $($args)*);
+    };
+    (@find [], $($args:tt)*) => {};
+    ($kind:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], [$( $name:ident ),*]) => {
+        $( #[$impl_attr] )*
+        impl $kind {
+            /// Look up the kind with this discriminant (i.e. the inverse of
+            /// `kind as u16`).
+            $( #[$fn_attr] )*
+            pub const fn from_u16(value: u16) -> $crate::_core::option::Option<$kind> {
+                $(
+                    // compare as isize so an out of range discriminant can't be truncated into a match
+                    if value as isize == $kind::$name as isize {
+                        return $crate::_core::option::Option::Some($kind::$name);
+                    }
+                )*
+
+                $crate::_core::option::Option::None
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_kind_set {
+    (@find [(kind_set = $set:ident) $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_kind_set!($set, $($args)*);
+    };
+    (@find [$other:tt $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_kind_set!(@find [$($rest)*], $($args)*);
+    };
+    (@find [], $($args:tt)*) => {};
+    ($set:ident, $kind:ident, $enum_name:ident, [$($vis:tt)*], [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], [$( $name:ident ),*]) => {
+        const _: () = $crate::_core::assert!(
+            <[&str]>::len(&[$( stringify!($name) ),*]) <= 64,
+            concat!(
+                "The `kind_set` option on `",
+                stringify!($enum_name),
+                "` only supports sum types with at most 64 variants"
+            ),
+        );
+
+        /// A set of kinds, stored as a bitmask with one bit per kind.
+        #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+        $($vis)* struct $set(pub u64);
+
+        $( #[$impl_attr] )*
+        impl $set {
+            /// Is `kind` in this set?
+            $( #[$fn_attr] )*
+            pub const fn contains(&self, kind: $kind) -> bool {
+                self.0 & kind.mask() != 0
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $kind {
+            /// This kind's bit in a bitmask, i.e. `1 << index()`.
+            $( #[$fn_attr] )*
+            pub const fn mask(&self) -> u64 {
+                1 << self.index()
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::ops::BitOr for $kind {
+            type Output = $set;
+
+            $( #[$fn_attr] )*
+            fn bitor(self, other: $kind) -> $set {
+                $set(self.mask() | other.mask())
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::ops::BitOr<$kind> for $set {
+            type Output = $set;
+
+            $( #[$fn_attr] )*
+            fn bitor(self, other: $kind) -> $set {
+                $set(self.0 | other.mask())
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl $crate::_core::ops::BitOr for $set {
+            type Output = $set;
+
+            $( #[$fn_attr] )*
+            fn bitor(self, other: $set) -> $set {
+                $set(self.0 | other.0)
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_box_ctor {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $method:ident, $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Create this variant, converting the value into its payload
+            /// (e.g. by boxing it).
+            $( #[$fn_attr] )*
+            pub fn $method<T>(value: T) -> $enum_name
+            where
+                $variant_type: $crate::_core::convert::From<T>,
+            {
+                $enum_name::$name(<$variant_type as $crate::_core::convert::From<T>>::from(value))
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_try_map {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $method:ident, $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Apply a fallible transformation to the payload if this is the
+            /// corresponding variant, otherwise return `self` unchanged.
+            $( #[$fn_attr] )*
+            pub fn $method<F, E>(self, f: F) -> $crate::_core::result::Result<$enum_name, E>
+            where
+                F: $crate::_core::ops::FnOnce($variant_type) -> $crate::_core::result::Result<$variant_type, E>,
+            {
+                match self {
+                    $enum_name::$name(value) => f(value).map($enum_name::$name),
+                    #[allow(unreachable_patterns)]
+                    other => $crate::_core::result::Result::Ok(other),
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_zip {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $method:ident, $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl $enum_name {
+            /// Pair up the payloads of two values which are both this
+            /// variant. The error names whichever value was a different
+            /// variant, checking `self` first.
+            $( #[$fn_attr] )*
+            pub fn $method(self, other: $enum_name) -> $crate::_core::result::Result<($variant_type, $variant_type), $crate::InvalidType> {
+                match (self, other) {
+                    ($enum_name::$name(left), $enum_name::$name(right)) => $crate::_core::result::Result::Ok((left, right)),
+                    #[allow(unreachable_patterns)]
+                    (left, right) => {
+                        let mismatched = match left {
+                            $enum_name::$name(_) => right,
+                            #[allow(unreachable_patterns)]
+                            _ => left,
+                        };
+
+                        $crate::_core::result::Result::Err($crate::InvalidType {
+                            expected_variant: $label,
+                            expected_type: stringify!($variant_type),
+                            actual_variant: $crate::SumType::variant(&mismatched),
+                            all_variants: $crate::SumType::variants(&mismatched),
+                            __non_exhaustive: (),
+                        })
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_from_iter {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $name:ident ($label:expr) => $variant_type:ty) => {
+        $( #[$impl_attr] )*
+        impl<A> $crate::_core::iter::FromIterator<A> for $enum_name
+        where
+            $variant_type: $crate::_core::iter::FromIterator<A>,
+        {
+            $( #[$fn_attr] )*
+            fn from_iter<I: $crate::_core::iter::IntoIterator<Item = A>>(iter: I) -> $enum_name {
+                $enum_name::$name($crate::_core::iter::FromIterator::from_iter(iter))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_serde_untagged {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_serde::Serialize for $enum_name {
+            $( #[$fn_attr] )*
+            fn serialize<S>(&self, serializer: S) -> $crate::_core::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::_serde::Serializer,
+            {
+                match *self {
+                    $(
+                        $enum_name::$name(ref value) => $crate::_serde::Serialize::serialize(value, serializer),
+                    )*
+                }
+            }
+        }
+
+        $( #[$impl_attr] )*
+        impl<'de> $crate::_serde::Deserialize<'de> for $enum_name {
+            $( #[$fn_attr] )*
+            fn deserialize<D>(deserializer: D) -> $crate::_core::result::Result<$enum_name, D::Error>
+            where
+                D: $crate::_serde::Deserializer<'de>,
+            {
+                // serde only accepts the crate's path as a string, so this
+                // can't go through `$crate`
+                #[derive($crate::_serde::Deserialize)]
+                #[serde(crate = "::sum_type::_serde", untagged)]
+                enum Untagged {
+                    $( $name($variant_type), )*
+                }
+
+                match <Untagged as $crate::_serde::Deserialize>::deserialize(deserializer)? {
+                    $(
+                        Untagged::$name(value) => $crate::_core::result::Result::Ok($enum_name::$name(value)),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_serde_via_repr {
+    (@find [(serde_via_struct) $($rest:tt)*], $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {
+        $crate::__sum_type_serde_via_repr!(@rename $enum_name, $fn_attrs, $impl_attrs, [], $variants);
+    };
+    (@find [$other:tt $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_serde_via_repr!(@find [$($rest)*], $($args)*);
+    };
+    (@find [], $($args:tt)*) => {};
+
+    // serde needs the tag as a literal, so we can't go through
+    // `__sum_type_label!()`
+    (@rename $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, [$($done:tt)*],
+        [{ $name:ident ($ty:ty) [(rename = $label:tt) $($opts:tt)*] } $($rest:tt)*]) => {
+        $crate::__sum_type_serde_via_repr!(@rename $enum_name, $fn_attrs, $impl_attrs,
+            [$($done)* { $name ($ty) [#[serde(rename = $label)]] }], [$($rest)*]);
+    };
+    (@rename $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $done:tt,
+        [{ $name:ident ($ty:ty) [$other:tt $($opts:tt)*] } $($rest:tt)*]) => {
+        $crate::__sum_type_serde_via_repr!(@rename $enum_name, $fn_attrs, $impl_attrs,
+            $done, [{ $name ($ty) [$($opts)*] } $($rest)*]);
+    };
+    (@rename $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, [$($done:tt)*],
+        [{ $name:ident ($ty:ty) [] } $($rest:tt)*]) => {
+        $crate::__sum_type_serde_via_repr!(@rename $enum_name, $fn_attrs, $impl_attrs,
+            [$($done)* { $name ($ty) [] }], [$($rest)*]);
+    };
+    (@rename $enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $done:tt, []) => {
+        $crate::__sum_type_serde_via_repr!(@impl $enum_name, $fn_attrs, $impl_attrs, $done);
+    };
+
+    (@impl $enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*],
+        [$( { $name:ident ($variant_type:ty) [$( #[$serde_attr:meta] )*] } )*]) => {
+        // serde only accepts paths as strings, so these can't go through
+        // `$crate`
+        const _: () = {
+            /// One optional field per variant, named after the variant's tag.
+            /// It borrows the payload so serializing doesn't need a clone.
+            #[derive($crate::_serde::Serialize)]
+            #[serde(crate = "::sum_type::_serde")]
+            #[allow(non_snake_case)]
+            struct Tagged<'a> {
+                $(
+                    $( #[$serde_attr] )*
+                    #[serde(skip_serializing_if = "::sum_type::_core::option::Option::is_none")]
+                    $name: $crate::_core::option::Option<&'a $variant_type>,
+                )*
+            }
+
+            #[derive($crate::_serde::Deserialize)]
+            #[serde(crate = "::sum_type::_serde", deny_unknown_fields)]
+            #[allow(non_snake_case)]
+            struct TaggedOwned {
+                $(
+                    $( #[$serde_attr] )*
+                    $name: $crate::_core::option::Option<$variant_type>,
+                )*
+            }
+
+            $( #[$impl_attr] )*
+            impl<'a> $crate::_core::convert::From<&'a $enum_name> for Tagged<'a> {
+                $( #[$fn_attr] )*
+                fn from(value: &'a $enum_name) -> Tagged<'a> {
+                    let mut tagged = Tagged {
+                        $( $name: $crate::_core::option::Option::None, )*
+                    };
+
+                    match *value {
+                        $( $enum_name::$name(ref payload) => tagged.$name = $crate::_core::option::Option::Some(payload), )*
+                    }
+
+                    tagged
+                }
+            }
+
+            $( #[$impl_attr] )*
+            impl $crate::_core::convert::TryFrom<TaggedOwned> for $enum_name {
+                type Error = &'static str;
+
+                $( #[$fn_attr] )*
+                fn try_from(tagged: TaggedOwned) -> $crate::_core::result::Result<$enum_name, &'static str> {
+                    let mut value = $crate::_core::option::Option::None;
+
+                    $(
+                        if let $crate::_core::option::Option::Some(payload) = tagged.$name {
+                            if value.is_some() {
+                                return $crate::_core::result::Result::Err("expected exactly one variant");
+                            }
+                            value = $crate::_core::option::Option::Some($enum_name::$name(payload));
+                        }
+                    )*
+
+                    value.ok_or("expected exactly one variant")
+                }
+            }
+
+            $( #[$impl_attr] )*
+            impl $crate::_serde::Serialize for $enum_name {
+                $( #[$fn_attr] )*
+                fn serialize<S>(&self, serializer: S) -> $crate::_core::result::Result<S::Ok, S::Error>
+                where
+                    S: $crate::_serde::Serializer,
+                {
+                    $crate::_serde::Serialize::serialize(&Tagged::from(self), serializer)
+                }
+            }
+
+            $( #[$impl_attr] )*
+            impl<'de> $crate::_serde::Deserialize<'de> for $enum_name {
+                $( #[$fn_attr] )*
+                fn deserialize<D>(deserializer: D) -> $crate::_core::result::Result<$enum_name, D::Error>
+                where
+                    D: $crate::_serde::Deserializer<'de>,
+                {
+                    let tagged = <TaggedOwned as $crate::_serde::Deserialize>::deserialize(deserializer)?;
+                    $crate::_core::convert::TryFrom::try_from(tagged)
+                        .map_err(<D::Error as $crate::_serde::de::Error>::custom)
+                }
+            }
+        };
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_serde_via_repr {
+    (@find [(serde_via_struct) $($rest:tt)*], $enum_name:ident, $($args:tt)*) => {
+        compile_error!(concat!(
+            "The `serde_via_struct` option on `",
+            stringify!($enum_name),
+            "` requires the `serde` feature of `sum_type`"
+        ));
+    };
+    (@find [$other:tt $($rest:tt)*], $($args:tt)*) => {
+        $crate::__sum_type_serde_via_repr!(@find [$($rest)*], $($args)*);
+    };
+    (@find [], $($args:tt)*) => {};
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_serde_untagged {
+    ($enum_name:ident, $($rest:tt)*) => {
+        compile_error!(concat!(
+            "The `serde_untagged` option on `",
+            stringify!($enum_name),
+            "` requires the `serde` feature of `sum_type`"
+        ));
+    };
+}
+
+#[cfg(feature = "serde_json")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_json {
+    ($enum_name:ident, [$( #[$fn_attr:meta] )*], [$( #[$impl_attr:meta] )*], $( $name:ident ($label:expr) => $variant_type:ty ),*) => {
+        $( #[$impl_attr] )*
+        impl $crate::_core::convert::From<$enum_name> for $crate::_serde_json::Value {
+            $( #[$fn_attr] )*
+            fn from(other: $enum_name) -> $crate::_serde_json::Value {
+                let entry = match other {
+                    $(
+                        $enum_name::$name(value) => ($label, $crate::_core::convert::Into::<$crate::_serde_json::Value>::into(value)),
+                    )*
+                };
+
+                $crate::_core::iter::FromIterator::from_iter($crate::_core::option::Option::Some(entry))
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde_json"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_json {
+    ($enum_name:ident, $($rest:tt)*) => {
+        compile_error!(concat!(
+            "The `json` option on `",
+            stringify!($enum_name),
+            "` requires the `serde_json` feature of `sum_type`"
+        ));
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_impls {
+    ($enum_name:ident, $fn_attrs:tt, $impl_attrs:tt, $( $name:ident ($label:expr) => $variant_type:ty ),*) => (
+        $crate::__assert_multiple_variants!($enum_name, $( $name ($label) => $variant_type ),*);
+        $crate::__assert_sized_variants!($enum_name, $( $name ($label) => $variant_type ),*);
+
+        $crate::__sum_type_trait!($enum_name, $fn_attrs, $impl_attrs, $($name ($label) => $variant_type),*);
+        $crate::__sum_type_as_ref_any!($enum_name, $fn_attrs, $impl_attrs, $($name ($label) => $variant_type),*);
+    )
+}
+
+/// The entire point.
+///
+/// # Attributes
+///
+/// Attributes and doc comments are passed through to the generated enum and
+/// its variants, and may be written in whichever order feels natural. The
+/// trailing comma after the last variant is optional.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     /// A sum type with a doc comment that spans
+///     /// multiple lines.
+///     #[derive(Debug, Clone, PartialEq)]
+///     /// More documentation after the derive.
+///     pub enum Documented {
+///         /// The first variant.
+///         First(u32),
+///         #[allow(missing_docs)]
+///         /// The second variant.
+///         Second(String)
+///     }
+/// }
+///
+/// sum_type! {
+///     /// A private sum type using lazy variant names.
+///     #[derive(Debug, PartialEq)]
+///     enum PrivateLazy { u32, String }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Documented::from(42), Documented::First(42));
+/// assert_eq!(PrivateLazy::from(42), PrivateLazy::u32(42));
+/// # }
+/// ```
+///
+/// Conditional attributes like `cfg_attr` work too, and the generated impls
+/// don't depend on whether they are active.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # #[cfg(feature = "serde")]
+/// # extern crate serde;
+/// # #[cfg(feature = "serde")]
+/// # extern crate serde_json;
+/// sum_type! {
+///     #[cfg_attr(feature = "serde", derive(serde::Serialize))]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         #[cfg_attr(feature = "serde", serde(rename = "first"))]
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(MySumType::from(42), MySumType::First(42));
+///
+/// #[cfg(feature = "serde")]
+/// assert_eq!(
+///     serde_json::to_string(&MySumType::First(42)).unwrap(),
+///     r#"{"first":42}"#
+/// );
+/// # }
+/// ```
+///
+/// Variants can also be `#[cfg(...)]`-gated. The generated impls only mention
+/// the variants which are actually compiled in.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use sum_type::SumType;
+///
+/// sum_type! {
+///     pub enum Configured {
+///         First(u32),
+///         #[cfg(any())]
+///         Second(String),
+///         #[cfg(all())]
+///         Third(u8),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Configured::from(1_u8).variants(), &["First", "Third"]);
+/// # }
+/// ```
+///
+/// A sum type still needs at least two variants once everything has been
+/// configured away.
+///
+/// ```rust,compile_fail,E0080
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     pub enum Configured {
+///         First(u32),
+///         #[cfg(any())]
+///         Second(String),
+///         #[cfg(any())]
+///         Third(u8),
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// Helper attributes used by derives, like serde's `#[serde(...)]`, stay
+/// attached to whichever enum or variant they were written on.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # extern crate serde;
+/// # extern crate serde_json;
+/// sum_type! {
+///     #[derive(Debug, PartialEq, serde::Deserialize)]
+///     #[serde(rename_all = "lowercase")]
+///     pub enum MySumType {
+///         First(u32),
+///         /// A variant with a doc comment before its helper attribute.
+///         #[serde(alias = "text")]
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let first: MySumType = serde_json::from_str(r#"{"first": 42}"#).unwrap();
+/// assert_eq!(first, MySumType::from(42));
+///
+/// let second: MySumType = serde_json::from_str(r#"{"text": "Hello"}"#).unwrap();
+/// assert_eq!(second, MySumType::Second(String::from("Hello")));
+/// # }
+/// ```
+///
+/// Several enums can be declared in the same invocation, each getting its own
+/// set of impls and options.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use sum_type::SumType;
+///
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Shape {
+///         Circle(f64),
+///         Square(u32),
+///     }
+///
+///     #[sum_type(kind = ColourKind)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum Colour {
+///         Named(String),
+///         Rgb((u8, u8, u8)),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Shape::from(1.5), Shape::Circle(1.5));
+/// assert_eq!(Shape::Square(2).variant(), "Square");
+///
+/// let red = Colour::from((255, 0, 0));
+/// assert_eq!(red, Colour::Rgb((255, 0, 0)));
+/// assert_eq!(red.kind(), ColourKind::Rgb);
+/// # }
+/// ```
+///
+/// The generated code only refers to items through fully qualified paths, so
+/// a sum type can also be declared inside a function body, even one where
+/// names like `Result`, `Option` or `Some` have been shadowed.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// #[allow(dead_code)]
+/// enum Shadowed { Some, None, Ok, Err }
+///
+/// # fn main() {
+/// #[allow(unused_imports)]
+/// use Shadowed::*;
+/// #[allow(dead_code)]
+/// type Result<T> = std::result::Result<T, String>;
+/// #[allow(dead_code)]
+/// struct Option;
+///
+/// sum_type! {
+///     #[sum_type(kind = LocalKind, from_str)]
+///     #[derive(Debug, Clone, PartialEq)]
+///     enum Local {
+///         Number(u32),
+///         Text(String),
+///     }
+/// }
+///
+/// let local = Local::from(String::from("hello"));
+/// assert_eq!(local.kind(), LocalKind::Text);
+/// assert_eq!("Number 5".parse::<Local>().unwrap(), Local::Number(5));
+/// # }
+/// ```
+///
+/// # Options
+///
+/// Extra functionality can be opted into by adding a `#[sum_type(...)]`
+/// attribute to the enum. The attribute is consumed by the macro and won't
+/// appear on the generated type. Multiple options may be provided, either in
+/// the same attribute or spread across several. Nothing beyond the core impls
+/// is generated without an option, so the extra methods and constants can't
+/// clash with items of your own.
+///
+/// ```rust,compile_fail
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(not_a_real_option)]
+///     pub enum Unknown { First(u32), Second(String) }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ## `try_sum` and `try_product`
+///
+/// Generates an associated `try_sum()` (or `try_product()`) function which
+/// adds (or multiplies) together the payloads of an iterator of values. Every
+/// item must be the same variant and all payloads must implement `Add` (or
+/// `Mul`). A [`CombineError`] says whether the variants were mixed or there
+/// weren't any values at all.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use sum_type::CombineError;
+///
+/// sum_type! {
+///     #[sum_type(try_sum, try_product)]
+///     #[derive(Debug, Copy, Clone, PartialEq)]
+///     pub enum Number {
+///         Integer(i64),
+///         Float(f64),
+///     }
+/// }
+///
+/// # fn main() {
+/// let integers = vec![Number::Integer(1), Number::Integer(2), Number::Integer(3)];
+/// assert_eq!(Number::try_sum(integers.clone()), Ok(Number::Integer(6)));
+/// assert_eq!(Number::try_product(integers), Ok(Number::Integer(6)));
+///
+/// let empty: Vec<Number> = Vec::new();
+/// let err = Number::try_sum(empty).unwrap_err();
+/// assert_eq!(err, CombineError::Empty);
+/// assert_eq!(err.to_string(), "there were no values to combine");
+///
+/// let mixed = vec![Number::Integer(1), Number::Float(2.0), Number::Integer(3)];
+/// match Number::try_sum(mixed).unwrap_err() {
+///     CombineError::MixedVariants(err) => {
+///         assert_eq!(err.expected_variant, "Integer");
+///         assert_eq!(err.actual_variant, "Float");
+///     }
+///     other => panic!("unexpected error: {}", other),
+/// }
+/// # }
+/// ```
+///
+/// ## `try_add_assign`
+///
+/// Generates a `try_add_assign()` method which adds another value's payload
+/// into `self` in place, for accumulating values without knowing their
+/// variant up front. Mismatched variants result in an [`InvalidType`] error
+/// and leave `self` untouched, and all payloads must implement `AddAssign`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(try_add_assign)]
+///     #[derive(Debug, Copy, Clone, PartialEq)]
+///     pub enum Number {
+///         Integer(i64),
+///         Float(f64),
+///     }
+/// }
+///
+/// # fn main() {
+/// let mut total = Number::Integer(1);
+///
+/// for value in vec![Number::Integer(2), Number::Integer(3), Number::Integer(4)] {
+///     total.try_add_assign(value).unwrap();
+/// }
+/// assert_eq!(total, Number::Integer(10));
+///
+/// let err = total.try_add_assign(Number::Float(0.5)).unwrap_err();
+/// assert_eq!(err.expected_variant, "Integer");
+/// assert_eq!(err.actual_variant, "Float");
+/// assert_eq!(total, Number::Integer(10));
+/// # }
+/// ```
+///
+/// ## `neg`
+///
+/// Implement `Neg` by negating the payload and keeping the same variant. Every
+/// payload must implement `Neg`, with itself as the output.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(neg)]
+///     #[derive(Debug, Copy, Clone, PartialEq)]
+///     pub enum Number {
+///         Integer(i64),
+///         Float(f64),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(-Number::Integer(5), Number::Integer(-5));
+/// assert_eq!(-Number::Float(-1.5), Number::Float(1.5));
+/// # }
+/// ```
+///
+/// ## `inline`
+///
+/// Adds `#[inline]` to every generated method, including those on the `From`
+/// and `TryFrom` impls, so they can be inlined across crate boundaries. The
+/// expanded output can be inspected with a tool like `cargo expand`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use sum_type::SumType;
+///
+/// sum_type! {
+///     #[sum_type(inline, try_sum)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum Inlined {
+///         First(u32),
+///         Second(u64),
+///     }
+/// }
+///
+/// # fn main() {
+/// let first = Inlined::from(5_u32);
+/// assert_eq!(first.variant(), "First");
+/// assert_eq!(first.downcast_ref::<u32>(), Some(&5));
+///
+/// let total = Inlined::try_sum(vec![Inlined::Second(1), Inlined::Second(2)]);
+/// assert_eq!(total, Ok(Inlined::Second(3)));
+/// # }
+/// ```
+///
+/// ## `builder = Builder`
+///
+/// Generates a `Builder` type which holds whichever variant was set last. Each
+/// variant which should get a setter on the builder is marked with
+/// `#[sum_type(builder = setter)]`. Any `impl_attr` attributes are applied to
+/// the builder as well.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(builder = ConfigBuilder)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum Config {
+///         #[sum_type(builder = with_first)]
+///         First(u32),
+///         #[sum_type(builder = with_second)]
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let config = ConfigBuilder::new().with_second("x".into()).build();
+/// assert_eq!(config, Some(Config::Second(String::from("x"))));
+///
+/// let config = ConfigBuilder::new()
+///     .with_second("x".into())
+///     .with_first(42)
+///     .build();
+/// assert_eq!(config, Some(Config::First(42)));
+///
+/// assert_eq!(ConfigBuilder::new().build(), None);
+/// # }
+/// ```
+///
+/// ## `exhaustive_match_helper`
+///
+/// Generates a `match_all!()` macro for matching on the sum type without
+/// repeating its name in every arm. Each arm names a variant and a pattern for
+/// its payload, and it expands to a plain `match`, so forgetting a variant is
+/// a compile error. Use `exhaustive_match_helper = name` to call the macro
+/// something else, for example when two sum types live in the same module.
+///
+/// Like any `macro_rules!` macro, it can only be used after the sum type's
+/// definition. It refers to the sum type by name, so using it from a child
+/// module needs the sum type imported there too.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(exhaustive_match_helper)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # fn main() {
+/// let describe = |value: MySumType| match_all!(value;
+///     First(n) => n as usize,
+///     Second(s) => s.len(),
+///     Third(ref bytes) => bytes.len() * 2,
+/// );
+///
+/// assert_eq!(describe(MySumType::First(42)), 42);
+/// assert_eq!(describe(MySumType::Second(String::from("Hello"))), 5);
+/// assert_eq!(describe(MySumType::Third(vec![1, 2, 3])), 6);
+/// # }
+/// ```
+///
+/// Leaving out a variant is caught by the underlying `match`.
+///
+/// ```rust,compile_fail
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(exhaustive_match_helper = match_my_sum_type)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let value = MySumType::First(42);
+/// let n = match_my_sum_type!(value;
+///     First(n) => n,
+/// );
+/// # }
+/// ```
+///
+/// ## `variant_key = Key`
+///
+/// Generates a `Key` wrapper which compares, orders and hashes the sum type
+/// by its variant's name alone, and implements `Borrow<str>` so map entries
+/// can be looked up by name. Its `Debug` impl only shows the variant's name,
+/// so the sum type itself doesn't need to implement `Debug`. Any
+/// `impl_attr` attributes are applied to the wrapper as well.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use std::collections::{BTreeMap, HashMap};
+///
+/// sum_type! {
+///     #[sum_type(variant_key = MySumTypeKey)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let mut latest = HashMap::new();
+/// latest.insert(MySumTypeKey(MySumType::First(1)), 1);
+/// latest.insert(MySumTypeKey(MySumType::First(2)), 2);
+/// latest.insert(MySumTypeKey(MySumType::from(String::from("Hi"))), 3);
+///
+/// assert_eq!(latest.len(), 2);
+/// assert_eq!(latest.get("First"), Some(&2));
+/// assert_eq!(latest.get("Second"), Some(&3));
+/// assert_eq!(latest.get("Third"), None);
+///
+/// let mut sorted = BTreeMap::new();
+/// sorted.insert(MySumTypeKey(MySumType::from(String::from("Hi"))), 3);
+/// sorted.insert(MySumTypeKey(MySumType::First(1)), 1);
+///
+/// assert_eq!(sorted.get("Second"), Some(&3));
+/// assert_eq!(format!("{:?}", sorted), r#"{MySumTypeKey("First"): 1, MySumTypeKey("Second"): 3}"#);
+/// # }
+/// ```
+///
+/// ## `from_str` and `ignore_case_from_str`
+///
+/// Implements `FromStr`, parsing strings like `"First 10"` by looking up the
+/// variant with that name and parsing the rest of the string as its payload.
+/// Surrounding whitespace is ignored, and `ignore_case_from_str` will also match
+/// variant names case-insensitively. Every payload must implement `FromStr`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(ignore_case_from_str)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum Setting {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!("first 10".parse(), Ok(Setting::First(10)));
+/// assert_eq!("FIRST 10".parse(), Ok(Setting::First(10)));
+/// assert_eq!("  Second  Hello ".parse(), Ok(Setting::Second(String::from("Hello"))));
+///
+/// let unknown = "Third 10".parse::<Setting>().unwrap_err();
+/// assert_eq!(unknown.variant, None);
+/// assert_eq!(unknown.all_variants, &["First", "Second"]);
+///
+/// let bad_payload = "first ten".parse::<Setting>().unwrap_err();
+/// assert_eq!(bad_payload.variant, Some("First"));
+/// # }
+/// ```
+///
+/// Without `ignore_case_from_str`, variant names must match exactly.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(from_str)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum Setting {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!("First 10".parse(), Ok(Setting::First(10)));
+/// assert!("first 10".parse::<Setting>().is_err());
+/// # }
+/// ```
+///
+/// ## `partial_eq_str`
+///
+/// Implements `PartialEq<str>` and `PartialEq<&str>`, comparing the name of
+/// the current variant. The payload is ignored entirely.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(partial_eq_str)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert!(MySumType::First(1) == "First");
+/// assert!(MySumType::First(1) != "Second");
+/// assert!(MySumType::Second(String::from("First")) != "First");
+/// assert!(MySumType::First(1) == *"First");
+/// # }
+/// ```
+///
+/// ## `partial_eq_option`
+///
+/// Implements `PartialEq<Option<T>>` for each variant type, where the sum type
+/// equals `Some(x)` only if it holds that variant with a payload equal to `x`.
+/// It never equals `None`. Every payload must implement `PartialEq`. Like the
+/// `From` impls, variants marked with [`skip_from`](#skip_from) are left out,
+/// so variants can share a type as long as only one of them is converted.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(partial_eq_option)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert!(MySumType::First(5) == Some(5_u32));
+/// assert!(MySumType::First(5) != Some(6_u32));
+/// assert!(MySumType::First(5) != None::<u32>);
+/// assert!(MySumType::First(5) != Some(String::from("5")));
+/// assert!(MySumType::Second(String::from("5")) == Some(String::from("5")));
+///
+/// sum_type! {
+///     #[sum_type(partial_eq_option)]
+///     pub enum Shared {
+///         Count(u32),
+///         #[sum_type(skip_from)]
+///         Index(u32),
+///     }
+/// }
+///
+/// assert!(Shared::Count(5) == Some(5_u32));
+/// assert!(Shared::Index(5) != Some(5_u32));
+/// # }
+/// ```
+///
+/// ## `checked_index`
+///
+/// Generates a `from_index_default()` constructor which creates the variant at a
+/// particular index (in declaration order) with a default payload, returning
+/// `None` when the index is out of range. Every payload must implement
+/// `Default`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(checked_index)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(MySumType::from_index_default(0), Some(MySumType::First(0)));
+/// assert_eq!(MySumType::from_index_default(1), Some(MySumType::Second(String::new())));
+/// assert_eq!(MySumType::from_index_default(2), Some(MySumType::Third(Vec::new())));
+/// assert_eq!(MySumType::from_index_default(3), None);
+/// # }
+/// ```
+///
+/// ## `from_ref`
+///
+/// Also implement `From<&T>` for each variant type by cloning the payload.
+/// Every payload must implement `Clone`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(from_ref)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let number = 42;
+/// let text = String::from("Hello");
+///
+/// assert_eq!(MySumType::from(&number), MySumType::First(42));
+/// assert_eq!(MySumType::from(&text), MySumType::Second(String::from("Hello")));
+/// assert_eq!(text, "Hello");
+/// # }
+/// ```
+///
+/// ## `try_from_ref_clone`
+///
+/// Also implement `TryFrom<&MySumType>` for each variant type, cloning the
+/// payload out of a borrowed sum type instead of consuming it. Every payload
+/// must implement `Clone`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use std::convert::TryFrom;
+///
+/// sum_type! {
+///     #[sum_type(try_from_ref_clone)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let thing = MySumType::Second(String::from("Hello"));
+///
+/// assert_eq!(String::try_from(&thing).unwrap(), "Hello");
+/// assert!(u32::try_from(&thing).is_err());
+/// // the original is still usable
+/// assert_eq!(thing, MySumType::Second(String::from("Hello")));
+/// # }
+/// ```
+///
+/// ## `reset_payload`
+///
+/// Generates a `reset_payload()` method which keeps the current variant but
+/// swaps its payload for the default value. Every payload must implement
+/// `Default`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(reset_payload)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let second = MySumType::Second(String::from("abc"));
+/// assert_eq!(second.reset_payload(), MySumType::Second(String::new()));
+/// assert_eq!(MySumType::First(42).reset_payload(), MySumType::First(0));
+/// # }
+/// ```
+///
+/// ## `debug_dyn`
+///
+/// Generates an `as_debug()` method which returns the payload as a
+/// `&dyn Debug`, letting you log the payload without knowing its type. Every
+/// payload must implement `Debug`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(debug_dyn)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let first = MySumType::First(52);
+/// assert_eq!(format!("{:?}", first.as_debug()), "52");
+///
+/// let second = MySumType::Second(String::from("Hello"));
+/// assert_eq!(format!("{:?}", second.as_debug()), "\"Hello\"");
+/// # }
+/// ```
+///
+/// ## `debug_indexed`
+///
+/// Implement `Debug` so it shows the variant's index alongside its name, which
+/// helps when correlating logs with numeric tags. Don't combine it with
+/// `#[derive(Debug)]`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(debug_indexed)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(format!("{:?}", MySumType::First(52)), "First#0(52)");
+/// assert_eq!(format!("{:?}", MySumType::from(String::from("Hi"))), r#"Second#1("Hi")"#);
+/// # }
+/// ```
+///
+/// ## `fmt_radix`
+///
+/// Implement `Binary`, `Octal`, `LowerHex` and `UpperHex` by forwarding to the
+/// payload, so integer sum types can be formatted in other bases. Every
+/// payload must implement those traits.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(fmt_radix)]
+///     pub enum Integer {
+///         Byte(u8),
+///         Word(u32),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(format!("{:x}", Integer::Byte(255)), "ff");
+/// assert_eq!(format!("{:#X}", Integer::Word(48879)), "0xBEEF");
+/// assert_eq!(format!("{:08b}", Integer::Byte(5)), "00000101");
+/// assert_eq!(format!("{:o}", Integer::Word(8)), "10");
+/// # }
+/// ```
+///
+/// ## `kind = Kind`
+///
+/// Generates a fieldless `Kind` enum with the same variants (and doc comments)
+/// as the sum type, plus a `kind()` method for getting the current variant's
+/// kind. The `Kind` enum is `Copy` and has a `const fn index()` giving its
+/// position in declaration order, so it can be used in `const` contexts. It
+/// defaults to the first variant.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(kind = MySumTypeKind)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         /// The first variant.
+///         First(u32),
+///         /// The second variant.
+///         Second(String),
+///         /// A list of bytes.
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// const SECOND: usize = MySumTypeKind::Second.index();
+///
+/// # fn main() {
+/// assert_eq!(SECOND, 1);
+/// assert_eq!(MySumTypeKind::First.index(), 0);
+/// assert_eq!(MySumTypeKind::default(), MySumTypeKind::First);
+///
+/// let third = MySumType::Third(vec![1, 2, 3]);
+/// assert_eq!(third.kind(), MySumTypeKind::Third);
+/// assert_eq!(third.kind().index(), 2);
+/// # }
+/// ```
+///
+/// A `Kind` can also be looked up by its variant's name.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use std::convert::TryFrom;
+///
+/// sum_type! {
+///     #[sum_type(kind = MySumTypeKind)]
+///     pub enum MySumType {
+///         First(u32),
+///         #[sum_type(rename = "second")]
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(MySumTypeKind::try_from("First"), Ok(MySumTypeKind::First));
+/// assert_eq!(MySumTypeKind::try_from("second"), Ok(MySumTypeKind::Second));
+///
+/// let err = MySumTypeKind::try_from("Third").unwrap_err();
+/// assert_eq!(err.variant, None);
+/// assert_eq!(err.all_variants, &["First", "second"]);
+///
+/// // FromStr works the same way
+/// assert_eq!("First".parse::<MySumTypeKind>(), Ok(MySumTypeKind::First));
+/// assert_eq!("second".parse::<MySumTypeKind>(), Ok(MySumTypeKind::Second));
+///
+/// let err = "Third".parse::<MySumTypeKind>().unwrap_err();
+/// assert_eq!(err.to_string(), "unknown variant, expected one of `First`, `second`");
+/// # }
+/// ```
+///
+/// Variants may be given an explicit discriminant, which is applied to the
+/// `Kind` enum (the sum type itself can't have one because its variants carry
+/// data). This is handy when the kind is used as a stable tag on the wire.
+/// Note that `index()` is still the position in declaration order, and that
+/// discriminants without a `kind` option are a compile error.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(kind = MySumTypeKind)]
+///     pub enum MySumType {
+///         First(u32) = 10,
+///         Second(String) = 20,
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(MySumType::First(1).kind() as i32, 10);
+/// assert_eq!(MySumType::Second(String::new()).kind() as i32, 20);
+/// assert_eq!(MySumType::Third(Vec::new()).kind() as i32, 21);
+/// assert_eq!(MySumTypeKind::Third.index(), 2);
+/// # }
+/// ```
+///
+/// Adding the `repr_kind_from_primitive` option as well generates a
+/// `const fn from_u16()` which goes the other way, decoding a tag back into
+/// its `Kind`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(kind = MySumTypeKind, repr_kind_from_primitive)]
+///     pub enum MySumType {
+///         First(u32) = 10,
+///         Second(String) = 20,
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// const SECOND: Option<MySumTypeKind> = MySumTypeKind::from_u16(20);
+///
+/// # fn main() {
+/// assert_eq!(SECOND, Some(MySumTypeKind::Second));
+///
+/// for value in vec![MySumType::First(1), MySumType::Second(String::new()), MySumType::Third(Vec::new())] {
+///     let tag = value.kind() as u16;
+///     assert_eq!(MySumTypeKind::from_u16(tag), Some(value.kind()));
+/// }
+///
+/// assert_eq!(MySumTypeKind::from_u16(0), None);
+/// # }
+/// ```
+///
+/// A discriminant which doesn't fit in a `u16` is never matched.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(kind = MySumTypeKind, repr_kind_from_primitive)]
+///     pub enum MySumType {
+///         Large(String) = 0x1_0001,
+///         Small(u32) = 1,
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(MySumTypeKind::from_u16(1), Some(MySumTypeKind::Small));
+/// # }
+/// ```
+///
+/// The `kind_set = MySumTypeKindSet` option generates a set of kinds backed by
+/// a `u64` bitmask, for things like event filters. Kinds and sets can be
+/// combined with `|` to make a bigger set, and each kind's bit is available
+/// from `mask()`. This only works for sum types with at most 64 variants.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(kind = MySumTypeKind, kind_set = MySumTypeKindSet)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # fn main() {
+/// let filter: MySumTypeKindSet = MySumTypeKind::First | MySumTypeKind::Third;
+///
+/// assert!(filter.contains(MySumTypeKind::First));
+/// assert!(!filter.contains(MySumTypeKind::Second));
+/// assert!(filter.contains(MySumType::Third(vec![1, 2, 3]).kind()));
+///
+/// assert_eq!(MySumTypeKind::Second.mask(), 0b010);
+/// assert_eq!(filter | MySumTypeKind::Second, MySumTypeKindSet(0b111));
+/// assert_eq!(filter | MySumTypeKindSet(0b010), MySumTypeKindSet(0b111));
+/// assert!(!MySumTypeKindSet::default().contains(MySumTypeKind::First));
+/// # }
+/// ```
+///
+/// ## `impl_attr(attr, ...)`
+///
+/// Place the given attributes on every generated `impl` block, for example to
+/// silence lints the generated code would otherwise trigger in your crate.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(impl_attr(allow(clippy::all), automatically_derived))]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(MySumType::from(42), MySumType::First(42));
+/// # }
+/// ```
+///
+/// Because the attributes really do end up on the generated impls, a `cfg`
+/// which never holds removes them entirely, leaving room for hand-written
+/// `From` impls.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(impl_attr(cfg(any())))]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// impl From<u32> for MySumType {
+///     fn from(value: u32) -> MySumType {
+///         MySumType::First(value + 1)
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(MySumType::from(42), MySumType::First(43));
+/// # }
+/// ```
+///
+/// ## `hash`
+///
+/// Implement `Hash` by hashing the variant's index followed by its payload,
+/// for when `#[derive(Hash)]` can't be used. Equal payloads in different
+/// variants hash differently.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use std::collections::HashMap;
+///
+/// sum_type! {
+///     #[sum_type(hash)]
+///     #[derive(Debug, PartialEq, Eq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(u64),
+///     }
+/// }
+///
+/// # fn main() {
+/// let mut names = HashMap::new();
+/// names.insert(MySumType::First(1), "first");
+/// names.insert(MySumType::Second(1), "second");
+///
+/// assert_eq!(names.len(), 2);
+/// assert_eq!(names[&MySumType::First(1)], "first");
+/// assert_eq!(names[&MySumType::Second(1)], "second");
+/// # }
+/// ```
+///
+/// ## `payloads_eq`
+///
+/// Generates a `payloads_eq()` method which compares only the payloads,
+/// ignoring which variant holds them. Every payload must implement
+/// `PartialEq`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(payloads_eq)]
+///     pub enum MySumType {
+///         Count(u32),
+///         #[sum_type(skip_from)]
+///         Total(u32),
+///         Name(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert!(MySumType::Count(5).payloads_eq(&MySumType::Total(5)));
+/// assert!(!MySumType::Count(5).payloads_eq(&MySumType::Total(6)));
+/// assert!(!MySumType::Count(5).payloads_eq(&MySumType::Name(String::from("5"))));
+/// # }
+/// ```
+///
+/// ## `ord_by_payload`
+///
+/// For sum types where every variant holds the same type, implement
+/// `PartialEq`, `Eq`, `PartialOrd` and `Ord` by comparing the payloads alone,
+/// ignoring which variant holds them. The payload type must implement `Ord`,
+/// and these traits shouldn't also be derived.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(ord_by_payload)]
+///     #[derive(Debug)]
+///     pub enum Reading {
+///         Sensor(u32),
+///         #[sum_type(skip_from)]
+///         Manual(u32),
+///     }
+/// }
+///
+/// sum_type! {
+///     #[sum_type(ord_by_payload)]
+///     pub enum Spaced {
+///         Packed(Vec<u8>),
+///         #[sum_type(skip_from)]
+///         Loose(Vec < u8 >),
+///     }
+/// }
+///
+/// # fn main() {
+/// let mut readings = vec![Reading::Sensor(7), Reading::Manual(3), Reading::Sensor(5)];
+/// readings.sort();
+///
+/// let payloads: Vec<u32> = readings
+///     .iter()
+///     .map(|r| match *r { Reading::Sensor(v) | Reading::Manual(v) => v })
+///     .collect();
+/// assert_eq!(payloads, vec![3, 5, 7]);
+/// assert_eq!(Reading::Sensor(1), Reading::Manual(1));
+/// # }
+/// ```
+///
+/// Variants holding different types can't be compared this way, and say so
+/// with a constant assertion. The types are compared as written, so a type
+/// alias counts as a different type.
+///
+/// ```rust,compile_fail,E0080
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(ord_by_payload)]
+///     pub enum Mixed {
+///         Number(u32),
+///         Text(String),
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ## `eq_by_variant_only`
+///
+/// Implement `PartialEq` and `Eq` by comparing only which variant is active,
+/// ignoring the payloads entirely. This is useful when the variant is the
+/// state and the payload is incidental.
+///
+/// Be careful, two values holding completely different payloads will compare
+/// equal, which is not what most people expect from `==`. Don't derive
+/// `PartialEq` as well.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(eq_by_variant_only)]
+///     #[derive(Debug)]
+///     pub enum State {
+///         Connecting(u32),
+///         Connected(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(State::Connecting(1), State::Connecting(999));
+/// assert_ne!(State::Connecting(1), State::Connected(String::from("1")));
+/// # }
+/// ```
+///
+/// ## `option_pair`
+///
+/// For sum types with exactly two variants, convert to and from a pair of
+/// `Option`s where exactly one is `Some`. Any other pair is handed back as the
+/// error.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use std::convert::TryFrom;
+///
+/// sum_type! {
+///     #[sum_type(option_pair)]
+///     #[derive(Debug, Clone, PartialEq)]
+///     pub enum Either {
+///         Left(u32),
+///         Right(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let left = Either::Left(42);
+/// let pair = <(Option<u32>, Option<String>)>::from(left.clone());
+/// assert_eq!(pair, (Some(42), None));
+/// assert_eq!(Either::try_from(pair), Ok(left));
+///
+/// let right = Either::Right(String::from("Hello"));
+/// let pair = <(Option<u32>, Option<String>)>::from(right.clone());
+/// assert_eq!(pair, (None, Some(String::from("Hello"))));
+/// assert_eq!(Either::try_from(pair), Ok(right));
+///
+/// assert_eq!(Either::try_from((None, None)), Err((None, None)));
+/// let both = (Some(1), Some(String::from("both")));
+/// assert_eq!(Either::try_from(both.clone()), Err(both));
+/// # }
+/// ```
+///
+/// Using it with any other number of variants is an error.
+///
+/// ```rust,compile_fail
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(option_pair)]
+///     pub enum Three {
+///         First(u32),
+///         Second(String),
+///         Third(f64),
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// ## `c_repr_tag = CSumType`
+///
+/// Generate a `#[repr(C, i32)]` copy of the sum type for passing across an FFI
+/// boundary, plus `From` conversions in both directions. Its layout matches a
+/// C struct holding an `int32_t` tag followed by a union of the payloads,
+/// where each variant's tag is its index. Any `impl_attr` attributes are
+/// applied to the copy as well.
+///
+/// The copy is only FFI-safe when every payload is, so a variant holding a
+/// `String` or `Vec<T>` can't be handed to C this way.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use std::mem;
+///
+/// sum_type! {
+///     #[sum_type(c_repr_tag = CMySumType)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(f32),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(mem::size_of::<CMySumType>(), 2 * mem::size_of::<u32>());
+/// assert_eq!(mem::align_of::<CMySumType>(), mem::align_of::<u32>());
+///
+/// let c_repr = CMySumType::from(MySumType::Second(1.5));
+/// let tag = unsafe { *(&c_repr as *const CMySumType as *const i32) };
+/// assert_eq!(tag, 1);
+/// assert_eq!(MySumType::from(c_repr), MySumType::Second(1.5));
+/// # }
+/// ```
+///
+/// ## `truthy(Variant, ...)`
+///
+/// Generate an `is_ok_like()` method which is true when the sum type holds one
+/// of the listed variants. This is handy as a filter predicate for
+/// result-like sum types.
+///
+/// The sum type (and a reference to it) also implements `Not`, reducing it to a
+/// `bool` which is true for the variants that *aren't* truthy.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(truthy(Success, Skipped))]
+///     pub enum Outcome {
+///         Success(u32),
+///         Failure(String),
+///         Skipped(()),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert!(Outcome::Success(42).is_ok_like());
+/// assert!(!Outcome::Failure(String::from("oops")).is_ok_like());
+/// assert!(Outcome::Skipped(()).is_ok_like());
+///
+/// let outcomes = vec![
+///     Outcome::Success(1),
+///     Outcome::Failure(String::from("oops")),
+///     Outcome::Skipped(()),
+/// ];
+/// let failures = outcomes.iter().filter(|outcome| !*outcome).count();
+/// assert_eq!(failures, 1);
+/// assert!(!Outcome::Failure(String::from("oops")));
+/// assert!(!!Outcome::Success(42));
+/// # }
+/// ```
+///
+/// ## `serde_untagged`
+///
+/// With the `serde` feature enabled, implement `Serialize` and `Deserialize`
+/// using just the payload, without any tag saying which variant it is.
+/// Deserializing tries each variant's type in declaration order and picks the
+/// first one which succeeds, so put the most specific variants first.
+///
+/// This uses serde's own derive under the hood, through the copy of `serde`
+/// which `sum_type` re-exports, so your crate doesn't need to depend on `serde`
+/// itself. serde can only be told where that copy lives with a string, though,
+/// so the generated code always refers to it as `::sum_type::_serde`. Your
+/// crate does need to depend on `sum_type` directly and under its own name,
+/// otherwise you'll get an "unresolved import `sum_type`" error.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # extern crate serde_json;
+/// # #[cfg(feature = "serde")]
+/// sum_type! {
+///     #[sum_type(serde_untagged)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(f64),
+///         Third(String),
+///     }
+/// }
+///
+/// # #[cfg(feature = "serde")]
+/// # fn main() {
+/// let second = MySumType::Second(1.5);
+/// let json = serde_json::to_string(&second).unwrap();
+/// assert_eq!(json, "1.5");
+/// assert_eq!(serde_json::from_str::<MySumType>(&json).unwrap(), second);
+///
+/// let third: MySumType = serde_json::from_str(r#""Hello""#).unwrap();
+/// assert_eq!(third, MySumType::Third(String::from("Hello")));
+///
+/// // 42 would also make a perfectly good `f64`, but `First` comes first
+/// let first: MySumType = serde_json::from_str("42").unwrap();
+/// assert_eq!(first, MySumType::First(42));
+/// # }
+/// # #[cfg(not(feature = "serde"))]
+/// # fn main() {}
+/// ```
+///
+/// ## `serde_via_struct`
+///
+/// With the `serde` feature enabled, implement `Serialize` and `Deserialize`
+/// by converting to and from a private struct with one optional field per
+/// variant, and letting serde's own derive handle that struct. Each field is
+/// named after its variant's tag, including any `rename`, and only the active
+/// one is serialized. That gives the same externally tagged format as
+/// deriving serde's traits on the enum, for self-describing formats like
+/// JSON. Deserializing fails unless exactly one variant's tag is present.
+///
+/// Like `serde_untagged`, your crate doesn't need to depend on `serde` itself,
+/// but it does need to depend on `sum_type` directly and under its own name.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # extern crate serde_json;
+/// # #[cfg(feature = "serde")]
+/// sum_type! {
+///     #[sum_type(serde_via_struct)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         #[sum_type(rename = "second")]
+///         Second(String),
+///     }
+/// }
+///
+/// # #[cfg(feature = "serde")]
+/// # fn main() {
+/// let first = MySumType::First(42);
+/// let json = serde_json::to_string(&first).unwrap();
+/// assert_eq!(json, r#"{"First":42}"#);
+/// assert_eq!(serde_json::from_str::<MySumType>(&json).unwrap(), first);
+///
+/// let second = MySumType::Second(String::from("Hello"));
+/// let json = serde_json::to_string(&second).unwrap();
+/// assert_eq!(json, r#"{"second":"Hello"}"#);
+/// assert_eq!(serde_json::from_str::<MySumType>(&json).unwrap(), second);
+///
+/// assert!(serde_json::from_str::<MySumType>(r#"{"Second":"Hello"}"#).is_err());
+/// assert!(serde_json::from_str::<MySumType>(r#"{}"#).is_err());
+/// assert!(serde_json::from_str::<MySumType>(r#"{"First":1,"second":"Hello"}"#).is_err());
+/// # }
+/// # #[cfg(not(feature = "serde"))]
+/// # fn main() {}
+/// ```
+///
+/// ## `json`
+///
+/// With the `serde_json` feature enabled, implement `From<MySumType>` for
+/// `serde_json::Value`, producing an object with the variant's name as its
+/// only key. Every payload must implement `Into<Value>`. This is handy for
+/// logging, when deserializing isn't needed.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # #[macro_use]
+/// # extern crate serde_json;
+/// # #[cfg(feature = "serde_json")]
+/// sum_type! {
+///     #[sum_type(json)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # #[cfg(feature = "serde_json")]
+/// # fn main() {
+/// use serde_json::Value;
+///
+/// assert_eq!(Value::from(MySumType::First(42)), json!({ "First": 42 }));
+/// assert_eq!(
+///     Value::from(MySumType::Second(String::from("Hello"))),
+///     json!({ "Second": "Hello" }),
+/// );
+/// assert_eq!(Value::from(MySumType::Third(vec![1, 2])), json!({ "Third": [1, 2] }));
+/// # }
+/// # #[cfg(not(feature = "serde_json"))]
+/// # fn main() {}
+/// ```
+///
+/// ## `doc_variants`
+///
+/// Append a list of each variant's name and payload type to the enum's own
+/// documentation, so `cargo doc` shows it without you having to keep it up to
+/// date. The same text is available as a `VARIANTS_DOC` constant. This only
+/// applies when the enum is declared with `sum_type!()`.
+///
+/// ```rust
+/// #![deny(missing_docs)]
+/// //! A crate which must document every public item.
+/// # #[macro_use]
+/// # extern crate sum_type;
+///
+/// sum_type! {
+///     #[sum_type(doc_variants)]
+///     pub enum MySumType {
+///         /// The first variant.
+///         First(u32),
+///         /// The second variant.
+///         #[sum_type(rename = "second")]
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(
+///     MySumType::VARIANTS_DOC,
+///     "This sum type holds one of:\n\n- `First`: `u32`\n- `second`: `String`\n",
+/// );
+/// # }
+/// ```
+///
+/// ## `tryfrom_by_index`
+///
+/// With the `alloc` feature enabled, generates a `payload_if_index()` method
+/// for decoders which already know which variant they expect. It gives back
+/// the boxed payload if the current variant has that index, and `None`
+/// otherwise.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # #[cfg(feature = "alloc")]
+/// sum_type! {
+///     #[sum_type(tryfrom_by_index)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # #[cfg(feature = "alloc")]
+/// # fn main() {
+/// let payload = MySumType::Second(String::from("Hello")).payload_if_index(1).unwrap();
+/// assert_eq!(payload.downcast_ref::<String>().unwrap(), "Hello");
+///
+/// assert!(MySumType::First(42).payload_if_index(1).is_none());
+/// # }
+/// # #[cfg(not(feature = "alloc"))]
+/// # fn main() {}
+/// ```
+///
+/// ## `into_iter`
+///
+/// With the `alloc` feature enabled, implement `IntoIterator` so iterating
+/// over a value yields its boxed payload exactly once. This lets a sum type
+/// be chained into pipelines which work with type-erased values.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # #[cfg(feature = "alloc")]
+/// sum_type! {
+///     #[sum_type(into_iter)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # #[cfg(feature = "alloc")]
+/// # fn main() {
+/// let mut items = Vec::new();
+///
+/// for item in MySumType::Second(String::from("Hello")) {
+///     items.push(item);
+/// }
+///
+/// assert_eq!(items.len(), 1);
+/// assert_eq!(items[0].downcast_ref::<String>().unwrap(), "Hello");
+/// # }
+/// # #[cfg(not(feature = "alloc"))]
+/// # fn main() {}
+/// ```
+///
+/// ## `optional`
+///
+/// Store every payload as an `Option`, for values which are filled in
+/// incrementally. Everything else (`From`, `TryFrom`, downcasting, ...) then
+/// works in terms of the `Option`, and an `is_set()` method says whether the
+/// current variant's payload has been filled in. Combine it with `variant()`
+/// or `kind()` to check a particular variant.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use sum_type::SumType;
+///
+/// sum_type! {
+///     #[sum_type(optional)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum Field {
+///         Number(u32),
+///         Text(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let mut field = Field::Number(None);
+/// assert!(!field.is_set());
+///
+/// field = Field::from(Some(42_u32));
+/// assert!(field.is_set());
+/// assert_eq!(field.variant(), "Number");
+/// assert_eq!(field.downcast_ref::<Option<u32>>(), Some(&Some(42)));
+///
+/// let text = Field::Text(Some(String::from("Hello")));
+/// assert!(text.is_set());
+/// assert!(text.variant_is::<Option<String>>());
+/// # }
+/// ```
+///
+/// ## `variant_table`
+///
+/// Generate a `VARIANT_TABLE` constant pairing each variant's index with its
+/// name, a `VARIANT_COUNT` constant, and a `for_each_variant_name()` function
+/// which visits every entry in the table.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use sum_type::SumType;
+///
+/// sum_type! {
+///     #[sum_type(variant_table)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # fn main() {
+/// let values = [
+///     MySumType::First(52),
+///     MySumType::Second(String::from("Hello")),
+///     MySumType::Third(vec![1, 2, 3]),
+/// ];
+///
+/// assert_eq!(MySumType::VARIANT_COUNT, 3);
+/// assert_eq!(MySumType::VARIANT_TABLE.len(), values.len());
+///
+/// for (&(index, name), value) in MySumType::VARIANT_TABLE.iter().zip(&values) {
+///     assert_eq!(value.variant_index(), index);
+///     assert_eq!(value.variant(), name);
+/// }
+///
+/// // or without zipping things up by hand
+/// let mut names = Vec::new();
+/// MySumType::for_each_variant_name(|index, name| names.push((index, name)));
+/// assert_eq!(names, vec![(0, "First"), (1, "Second"), (2, "Third")]);
+/// # }
+/// ```
+///
+/// ## `variant_index_of_name`
+///
+/// Generate a `const fn variant_index_of_name()` which looks up a variant's
+/// index by name, so it can be used to build lookup tables. For decoders
+/// which accept names in either case, `variant_position()` does the same
+/// lookup while ignoring ASCII case.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(variant_index_of_name)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// const THIRD: Option<usize> = MySumType::variant_index_of_name("Third");
+/// const FOURTH: Option<usize> = MySumType::variant_index_of_name("Fourth");
+/// const SECOND: Option<usize> = MySumType::variant_position("SECOND");
+///
+/// # fn main() {
+/// assert_eq!(THIRD, Some(2));
+/// assert_eq!(FOURTH, None);
+/// assert_eq!(MySumType::variant_index_of_name("First"), Some(0));
+///
+/// assert_eq!(SECOND, Some(1));
+/// assert_eq!(MySumType::variant_position("tHiRd"), Some(2));
+/// assert_eq!(MySumType::variant_position("Fourth"), None);
+/// # }
+/// ```
+///
+/// ## `to_variant_index_map`
+///
+/// Generate a `to_variant_index_map()` method for sparse dispatch tables keyed
+/// by variant index. It builds an array with one slot per variant, where only
+/// the current variant's slot (at `variant_index()`) holds the value.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(to_variant_index_map)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # fn main() {
+/// let value = MySumType::Second(String::from("Hello"));
+/// let slots: [Option<&str>; 3] = value.to_variant_index_map("handler");
+/// assert_eq!(slots, [None, Some("handler"), None]);
+/// # }
+/// ```
+///
+/// ## `is_unit_variant`
+///
+/// Sum types can't have unit variants, but a variant holding `()` (or any
+/// other zero-sized type) plays the same role. This generates a
+/// `const fn is_unit_variant()` which tells you which variants those are.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(is_unit_variant)]
+///     pub enum Message {
+///         Ping(()),
+///         Text(String),
+///     }
+/// }
+///
+/// const PING_IS_UNIT: bool = Message::is_unit_variant(0);
+///
+/// # fn main() {
+/// assert!(PING_IS_UNIT);
+/// assert!(!Message::is_unit_variant(1));
+/// assert!(!Message::is_unit_variant(2));
+/// # }
+/// ```
+///
+/// ## `payload_size`
+///
+/// Generate a `payload_size()` method giving the size of the current payload.
+/// Payloads are always stored inline, so something like a boxed or borrowed
+/// slice reports the size of its (fat) pointer rather than the data it points
+/// to.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use std::mem;
+///
+/// sum_type! {
+///     #[sum_type(payload_size)]
+///     pub enum Packet {
+///         Byte(u8),
+///         Chunk(&'static [u8]),
+///         Owned(Box<[u8]>),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Packet::Byte(1).payload_size(), 1);
+///
+/// let chunk = Packet::Chunk(b"Hello, World!");
+/// assert_eq!(chunk.payload_size(), 2 * mem::size_of::<usize>());
+///
+/// let owned = Packet::Owned(vec![0; 1024].into_boxed_slice());
+/// assert_eq!(owned.payload_size(), mem::size_of::<Box<[u8]>>());
+/// # }
+/// ```
+///
+/// ## `variants_meta`
+///
+/// Generate a `VARIANTS_META` table which gathers each variant's name,
+/// payload type, index and doc comment in one place, for tooling which wants
+/// all of them. The same table is available from `variant_metadata()`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(variants_meta)]
+///     pub enum MySumType {
+///         /// The first variant.
+///         First(u32),
+///         #[sum_type(rename = "second")]
+///         Second(String),
+///         /// A list of bytes.
+///         ///
+///         ///     let bytes = vec![1, 2, 3];
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # fn main() {
+/// let meta = MySumType::VARIANTS_META;
+/// assert_eq!(meta.len(), 3);
+/// assert_eq!(MySumType::variant_metadata(), meta);
+///
+/// assert_eq!(meta[0].name, "First");
+/// assert_eq!(meta[0].type_name, "u32");
+/// assert_eq!(meta[0].index, 0);
+/// assert_eq!(meta[0].doc, Some("The first variant."));
+///
+/// assert_eq!(meta[1].name, "second");
+/// assert_eq!(meta[1].type_name, "String");
+/// assert_eq!(meta[1].doc, None);
+///
+/// assert_eq!(meta[2].type_name, "Vec<u8>");
+/// assert_eq!(meta[2].index, 2);
+/// assert_eq!(meta[2].doc, Some("A list of bytes.\n\n    let bytes = vec![1, 2, 3];"));
+/// # }
+/// ```
+///
+/// The doc comments are also available from a `variant_doc()` method, or
+/// all at once from `variants_doc_table()` (e.g. for a help message).
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(variants_meta)]
+///     pub enum Command {
+///         /// Print a greeting.
+///         Greet(String),
+///         /// Exit with the given code.
+///         ///
+///         ///     exit 1
+///         ///
+///         /// Defaults to zero.
+///         Exit(i32),
+///         Undocumented(f64),
+///     }
+/// }
+///
+/// # fn main() {
+/// let greet = Command::Greet(String::from("World"));
+/// assert_eq!(greet.variant_doc(), Some("Print a greeting."));
+///
+/// let exit = Command::Exit(1);
+/// assert_eq!(
+///     exit.variant_doc(),
+///     Some("Exit with the given code.\n\n    exit 1\n\nDefaults to zero."),
+/// );
+///
+/// assert_eq!(Command::Undocumented(1.5).variant_doc(), None);
+///
+/// let table: Vec<_> = Command::variants_doc_table().collect();
+/// assert_eq!(
+///     table,
+///     vec![
+///         ("Greet", Some("Print a greeting.")),
+///         ("Exit", Some("Exit with the given code.\n\n    exit 1\n\nDefaults to zero.")),
+///         ("Undocumented", None),
+///     ]
+/// );
+/// # }
+/// ```
+///
+/// ## `as_any`
+///
+/// Generate an `active_as_any()` method returning the same `&dyn Any` as the
+/// `AsRef<dyn Any>` impl, and a `variant_ref_any_iter()` method which wraps it
+/// in a one-element iterator for use with iterator combinators.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(as_any)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # fn main() {
+/// let second = MySumType::Second(String::from("Hello"));
+/// assert!(second.active_as_any().is::<String>());
+///
+/// let payloads: Vec<_> = second.variant_ref_any_iter().collect();
+/// assert_eq!(payloads.len(), 1);
+/// assert_eq!(payloads[0].downcast_ref::<String>().unwrap(), "Hello");
+///
+/// let texts: Vec<&String> = second
+///     .variant_ref_any_iter()
+///     .filter_map(|payload| payload.downcast_ref::<String>())
+///     .collect();
+/// assert_eq!(texts, vec!["Hello"]);
+/// # }
+/// ```
+///
+/// ## `into_parts`
+///
+/// Generate an `into_parts()` method which splits the sum type into its
+/// variant's index and a boxed, type-erased payload. This requires the
+/// `alloc` feature.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// # #[cfg(feature = "alloc")]
+/// sum_type! {
+///     #[sum_type(into_parts)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # #[cfg(feature = "alloc")]
+/// # fn main() {
+/// use sum_type::SumType;
+///
+/// let second = MySumType::Second(String::from("Hello"));
+/// let index = second.variant_index();
+///
+/// let (got, payload) = second.into_parts();
+/// assert_eq!(got, index);
+/// assert_eq!(*payload.downcast::<String>().unwrap(), "Hello");
+///
+/// let (index, payload) = MySumType::Third(vec![1, 2, 3]).into_parts();
+/// assert_eq!(index, 2);
+/// assert_eq!(*payload.downcast::<Vec<u8>>().unwrap(), vec![1, 2, 3]);
+/// # }
+/// # #[cfg(not(feature = "alloc"))]
+/// # fn main() {}
+/// ```
+///
+/// ## `fold_ref`
+///
+/// Generate a `fold_ref()` method for taking a sum type apart without a
+/// `match` or consuming it. It takes one function per variant, in
+/// declaration order.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(fold_ref)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # fn main() {
+/// let size = |value: &MySumType| value.fold_ref(
+///     |n| *n as usize,
+///     |s| s.len(),
+///     |bytes| bytes.len(),
+/// );
+///
+/// let second = MySumType::Second(String::from("Hello"));
+/// assert_eq!(size(&second), 5);
+/// assert_eq!(size(&MySumType::First(42)), 42);
+/// assert_eq!(second, MySumType::Second(String::from("Hello")));
+/// # }
+/// ```
+///
+/// ## `collapse`
+///
+/// When every variant's type can be converted into the same type, the
+/// generated `collapse()` method does the conversion without you needing to
+/// say which variant you have.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(collapse)]
+///     pub enum Text {
+///         Owned(String),
+///         Borrowed(&'static str),
+///         Letter(char),
+///     }
+/// }
+///
+/// # fn main() {
+/// let words: Vec<String> = vec![
+///     Text::Owned(String::from("Hello")),
+///     Text::Borrowed("World"),
+///     Text::Letter('!'),
+/// ]
+/// .into_iter()
+/// .map(Text::collapse)
+/// .collect();
+///
+/// assert_eq!(words, vec!["Hello", "World", "!"]);
+/// # sum_type! { #[sum_type(collapse)] pub enum B { Left(u8), Right(u16) } }
+/// # let _: u32 = B::Left(1).collapse();
+/// # }
+/// ```
+///
+/// ## `retag`
+///
+/// Generate a `retag()` method for moving the payload into another variant
+/// holding the same type, which is handy for state machines where only the
+/// tag changes. The variants sharing a type need the
+/// [`skip_from`](#skip_from) variant option.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use sum_type::RetagError;
+///
+/// sum_type! {
+///     #[sum_type(retag)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum Job {
+///         Queued(u32),
+///         #[sum_type(skip_from)]
+///         Running(u32),
+///         Failed(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let job = Job::from(7);
+/// assert_eq!(job, Job::Queued(7));
+///
+/// let job = job.retag("Running").unwrap();
+/// assert_eq!(job, Job::Running(7));
+///
+/// match job.retag("Failed").unwrap_err() {
+///     RetagError::WrongType(err) => {
+///         assert_eq!(err.expected_variant, "Failed");
+///         assert_eq!(err.expected_type, "String");
+///         assert_eq!(err.actual_variant, "Running");
+///     }
+///     other => panic!("unexpected error: {}", other),
+/// }
+///
+/// let err = Job::Queued(7).retag("Finished").unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "unknown variant, expected one of `Queued`, `Running`, `Failed`",
+/// );
+/// # }
+/// ```
+///
+/// # Variant Options
+///
+/// Some options apply to a single variant, in which case the
+/// `#[sum_type(...)]` attribute goes on the variant instead. Because
+/// `macro_rules!` can't create new identifiers, options which generate a
+/// method take the method's name.
+///
+/// ## `copy = method`
+///
+/// Generates a `method(&self)` accessor which returns a copy of the payload
+/// instead of a reference, leaving the original untouched. The payload must
+/// implement `Copy`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         #[sum_type(copy = first)]
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let first = MySumType::First(52);
+/// assert_eq!(first.first(), Some(52));
+/// // the accessor doesn't move out of `first`
+/// assert_eq!(first, MySumType::First(52));
+///
+/// let second = MySumType::Second(String::from("Hello"));
+/// assert_eq!(second.first(), None);
+/// # }
+/// ```
+///
+/// ## `rename = "name"`
+///
+/// Changes the name reported by `variant()`, `variants()`, and anything else
+/// which refers to the variant by name (e.g. `InvalidType` and `from_str`). The
+/// Rust identifier is unchanged.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use std::convert::TryFrom;
+/// use sum_type::SumType;
+///
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         #[sum_type(rename = "first_kind")]
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let first = MySumType::First(52);
+/// assert_eq!(first.variant(), "first_kind");
+/// assert_eq!(first.variants(), &["first_kind", "Second"]);
+///
+/// let err = String::try_from(first).unwrap_err();
+/// assert_eq!(err.actual_variant, "first_kind");
+/// # }
+/// ```
+///
+/// ## `flatten(Type, ...)`
+///
+/// For a variant which wraps another sum type, implements `From` for each of
+/// the listed types by converting them into the inner sum type first. This lets
+/// you skip a level of wrapping, although each listed type mustn't also be the
+/// payload of another variant.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Inner {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Outer {
+///         #[sum_type(flatten(u32, String))]
+///         Nested(Inner),
+///         Other(f64),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Outer::from(42_u32), Outer::Nested(Inner::First(42)));
+/// assert_eq!(
+///     Outer::from(String::from("Hello")),
+///     Outer::Nested(Inner::Second(String::from("Hello")))
+/// );
+/// assert_eq!(Outer::from(Inner::First(1)), Outer::Nested(Inner::First(1)));
+/// # }
+/// ```
+///
+/// ## `box_ctor = method`
+///
+/// Generates a `method()` constructor which converts its argument into the
+/// variant's payload using `From`. This is mainly useful for recursive sum
+/// types, where the payload needs to be boxed.
+///
+/// Note that a `Box<Self>` payload conflicts with the standard library's
+/// blanket `TryFrom` impl (because `Box<Self>: From<Self>`), so recursive
+/// payloads should box something else, like a tuple or array.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Expr {
+///         Num(i64),
+///         #[sum_type(box_ctor = add)]
+///         Add(Box<(Expr, Expr)>),
+///         #[sum_type(box_ctor = mul)]
+///         Mul(Box<[Expr; 2]>),
+///     }
+/// }
+///
+/// trait Eval {
+///     fn eval(&self) -> i64;
+/// }
+///
+/// impl Eval for i64 {
+///     fn eval(&self) -> i64 { *self }
+/// }
+///
+/// impl Eval for Box<(Expr, Expr)> {
+///     fn eval(&self) -> i64 { self.0.eval() + self.1.eval() }
+/// }
+///
+/// impl Eval for Box<[Expr; 2]> {
+///     fn eval(&self) -> i64 { self[0].eval() * self[1].eval() }
+/// }
+///
+/// impl Eval for Expr {
+///     fn eval(&self) -> i64 {
+///         sum_type::defer!(Expr as *self; Num | Add | Mul => |ref value| value.eval())
+///     }
+/// }
+///
+/// # fn main() {
+/// // (1 + 2) * 4
+/// let expr = Expr::mul([
+///     Expr::add((Expr::Num(1), Expr::Num(2))),
+///     Expr::Num(4),
+/// ]);
+///
+/// assert_eq!(expr.eval(), 12);
+/// # }
+/// ```
+///
+/// ## `try_map = method`
+///
+/// Generates a `method()` which applies a fallible transformation to the
+/// payload when the value is this variant, propagating any error. Other
+/// variants are passed through untouched.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         #[sum_type(try_map = try_map_first)]
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// fn double(value: u32) -> Result<u32, &'static str> {
+///     value.checked_mul(2).ok_or("overflow")
+/// }
+///
+/// # fn main() {
+/// assert_eq!(MySumType::First(21).try_map_first(double), Ok(MySumType::First(42)));
+/// assert_eq!(MySumType::First(u32::max_value()).try_map_first(double), Err("overflow"));
+///
+/// let second = MySumType::Second(String::from("Hello"));
+/// assert_eq!(second.try_map_first(double), Ok(MySumType::Second(String::from("Hello"))));
+/// # }
+/// ```
+///
+/// ## `zip = method`
+///
+/// Generates a `method()` which pairs up the payloads of two values when both
+/// are this variant, for combining values whose type is only known at
+/// runtime. Anything else results in an [`InvalidType`] error.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         #[sum_type(zip = zip_first)]
+///         First(u32),
+///         #[sum_type(zip = zip_second)]
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(MySumType::First(1).zip_first(MySumType::First(2)), Ok((1, 2)));
+///
+/// let err = MySumType::First(1)
+///     .zip_first(MySumType::Second(String::from("2")))
+///     .unwrap_err();
+/// assert_eq!(err.expected_variant, "First");
+/// assert_eq!(err.actual_variant, "Second");
+///
+/// let err = MySumType::First(1).zip_second(MySumType::Second(String::new())).unwrap_err();
+/// assert_eq!(err.expected_variant, "Second");
+/// assert_eq!(err.actual_variant, "First");
+/// # }
+/// ```
+///
+/// ## `skip_from`
+///
+/// Don't generate the `From`, `TryFrom` and `Option` conversions for this
+/// variant's type. This lets several variants hold the same type, which would
+/// otherwise give conflicting impls.
+///
+/// The [`retag`](#retag) option can then move a payload between those
+/// variants.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Job {
+///         Queued(u32),
+///         #[sum_type(skip_from)]
+///         Running(u32),
+///         Failed(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Job::from(7), Job::Queued(7));
+/// # }
+/// ```
+///
+/// ## `from`
+///
+/// When there are more variants sharing a type than not, it's easier to opt
+/// in. With `#[sum_type(from_variant_attr)]` on the enum, only variants marked
+/// with `from` get the `From`, `TryFrom` and `Option` conversions.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use std::convert::TryFrom;
+///
+/// sum_type! {
+///     #[sum_type(from_variant_attr)]
+///     #[derive(Debug, PartialEq)]
+///     pub enum Id {
+///         #[sum_type(from)]
+///         User(u32),
+///         Group(u32),
+///         Role(u32),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Id::from(7), Id::User(7));
+///
+/// // the conversions back out only go through the marked variant too
+/// assert_eq!(u32::try_from(Id::User(7)), Ok(7));
+/// assert!(u32::try_from(Id::Group(7)).is_err());
+/// # }
+/// ```
+///
+/// Unmarked variants don't have any conversions.
+///
+/// ```rust,compile_fail
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[sum_type(from_variant_attr)]
+///     pub enum Unmarked {
+///         First(u32),
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let _ = Unmarked::from(7);
+/// # }
+/// ```
+///
+/// ## `from_iter`
+///
+/// Implement `FromIterator` by collecting into this variant's payload. Only
+/// one variant may use it.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum MySumType {
+///         First(u32),
+///         Second(String),
+///         #[sum_type(from_iter)]
+///         Third(Vec<u8>),
+///     }
+/// }
+///
+/// # fn main() {
+/// let bytes: MySumType = (1..4).collect();
+/// assert_eq!(bytes, MySumType::Third(vec![1, 2, 3]));
+/// # }
+/// ```
+///
+/// ## `ok` and `err`
+///
+/// Tag the two variants of a `Result`-like sum type to generate
+/// `into_result()` and `from_result()`, plus the matching `From` impls. The
+/// `Try` trait isn't stable, but converting to a `Result` lets you use `?`.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Outcome {
+///         #[sum_type(ok)]
+///         Success(u32),
+///         #[sum_type(err)]
+///         Failure(String),
+///     }
+/// }
+///
+/// fn double(outcome: Outcome) -> Result<u32, String> {
+///     let value = outcome.into_result()?;
+///     Ok(value * 2)
+/// }
+///
+/// # fn main() {
+/// assert_eq!(double(Outcome::Success(21)), Ok(42));
+/// assert_eq!(double(Outcome::Failure(String::from("oops"))), Err(String::from("oops")));
+///
+/// let outcome = Outcome::from_result(Ok(1));
+/// assert_eq!(outcome, Outcome::Success(1));
+/// assert_eq!(Result::from(outcome), Ok(1));
+/// # }
+/// ```
+///
+/// ## `bytes`
+///
+/// Tag variants whose payload implements `AsRef<[u8]>` (e.g. `Vec<u8>` or
+/// `&'static [u8]`) to generate an `as_bytes()` method, which borrows the
+/// payload's bytes when on one of those variants and returns `None` otherwise.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// sum_type! {
+///     pub enum Body {
+///         #[sum_type(bytes)]
+///         Owned(Vec<u8>),
+///         #[sum_type(bytes)]
+///         Static(&'static [u8]),
+///         Length(usize),
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Body::Owned(vec![1, 2, 3]).as_bytes(), Some(&[1, 2, 3][..]));
+/// assert_eq!(Body::Static(b"Hi").as_bytes(), Some(&b"Hi"[..]));
+/// assert_eq!(Body::Length(3).as_bytes(), None);
+/// # }
+/// ```
+///
+/// [`InvalidType`]: struct.InvalidType.html
+/// [`CombineError`]: enum.CombineError.html
+#[macro_export]
+macro_rules! sum_type {
+    ( $($body:tt)* ) => {
+        $crate::__sum_type_parse!(@attrs define [] [] $($body)*);
+    };
+}
+
+/// Generate the same impls as [`sum_type!()`] for an enum which is declared
+/// separately.
+///
+/// The enum's variants need to be restated (attributes other than
+/// `#[sum_type(...)]` and doc comments are ignored), but the enum itself is
+/// left alone so it shows up in `rustdoc` exactly as written.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use sum_type::SumType;
+///
+/// /// A plain enum, declared without any macros.
+/// #[derive(Debug, Clone, PartialEq)]
+/// pub enum MySumType {
+///     /// The first variant.
+///     First(u32),
+///     /// The second variant.
+///     Second(String),
+/// }
+///
+/// sum_type_derive! {
+///     #[sum_type(kind = MySumTypeKind)]
+///     pub enum MySumType {
+///         /// The first variant.
+///         First(u32),
+///         /// The second variant.
+///         Second(String),
+///     }
+/// }
+///
+/// # fn main() {
+/// let first = MySumType::from(52);
+/// assert_eq!(first, MySumType::First(52));
+/// assert_eq!(first.variant(), "First");
+/// assert_eq!(first.kind(), MySumTypeKind::First);
+/// # }
+/// ```
+///
+/// [`sum_type!()`]: macro.sum_type.html
+#[macro_export]
+macro_rules! sum_type_derive {
+    ( $($body:tt)* ) => {
+        $crate::__sum_type_parse!(@attrs derive [] [] $($body)*);
+    };
+}
 
-            }
-       )*
-    }
+/// Create a sum type from a bare list of types, naming each variant after the
+/// last segment of its type (ignoring any generic arguments).
+///
+/// This is mainly useful when the list of types comes from another macro.
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate sum_type;
+/// use sum_type::SumType;
+///
+/// sum_type_from_types! {
+///     #[derive(Debug, PartialEq)]
+///     pub MySumType; u32, std::string::String, Vec<u8>
+/// }
+///
+/// # fn main() {
+/// let first = MySumType::from(52_u32);
+/// assert_eq!(first, MySumType::u32(52));
+/// assert_eq!(first.variants(), &["u32", "String", "Vec"]);
+///
+/// assert_eq!(MySumType::from(String::from("Hello")), MySumType::String(String::from("Hello")));
+/// assert_eq!(MySumType::from(vec![1, 2, 3]), MySumType::Vec(vec![1, 2, 3]));
+/// # }
+/// ```
+///
+/// Any `#[sum_type(...)]` options are passed through to [`sum_type!()`].
+///
+/// [`sum_type!()`]: macro.sum_type.html
+#[macro_export]
+macro_rules! sum_type_from_types {
+    ( $( #[$($attr:tt)*] )* pub $name:ident; $($types:tt)* ) => {
+        $crate::__sum_type_from_types!([$( #[$($attr)*] )*] [pub] $name [] [] $($types)*);
+    };
+    ( $( #[$($attr:tt)*] )* $name:ident; $($types:tt)* ) => {
+        $crate::__sum_type_from_types!([$( #[$($attr)*] )*] [] $name [] [] $($types)*);
+    };
 }
 
+// Munch through a list of types, remembering each type's path so the variant
+// can be named after its last segment.
 #[doc(hidden)]
 #[macro_export]
-macro_rules! __sum_type_from {
-    ($enum_name:ident, $( $name:ident => $variant_type:ty ),*) => {
-       $(
-            impl From<$variant_type> for $enum_name {
-                fn from(other: $variant_type) -> $enum_name {
-                    $enum_name::$name(other)
-                }
+macro_rules! __sum_type_from_types {
+    ([$($attrs:tt)*] [$($vis:tt)*] $name:ident [$( $var_name:ident ($($var_ty:tt)*) )*] []) => {
+        $crate::sum_type! {
+            $($attrs)*
+            $($vis)* enum $name {
+                $( $var_name($($var_ty)*), )*
             }
-        )*
-    }
+        }
+    };
+    ($attrs:tt $vis:tt $name:ident $done:tt [$($path:tt)*] :: $($rest:tt)*) => {
+        $crate::__sum_type_from_types!($attrs $vis $name $done [$($path)* ::] $($rest)*);
+    };
+    ($attrs:tt $vis:tt $name:ident $done:tt [$($path:tt)*] $segment:ident :: $($rest:tt)*) => {
+        $crate::__sum_type_from_types!($attrs $vis $name $done [$($path)* $segment ::] $($rest)*);
+    };
+    ($attrs:tt $vis:tt $name:ident [$($done:tt)*] [$($path:tt)*] $last:ident < $( $generic:ty ),+ > $(, $($rest:tt)*)?) => {
+        $crate::__sum_type_from_types!($attrs $vis $name
+            [$($done)* $last ($($path)* $last<$( $generic ),+>)]
+            []
+            $($($rest)*)?);
+    };
+    ($attrs:tt $vis:tt $name:ident [$($done:tt)*] [$($path:tt)*] $last:ident $(, $($rest:tt)*)?) => {
+        $crate::__sum_type_from_types!($attrs $vis $name
+            [$($done)* $last ($($path)* $last)]
+            []
+            $($($rest)*)?);
+    };
 }
 
 #[doc(hidden)]
 #[macro_export]
-macro_rules! __sum_type_trait {
-    ($enum_name:ident, $( $name:ident => $variant_type:ty ),*) => {
-        impl $crate::SumType for $enum_name {
-            fn variants(&self) -> &'static [ &'static str] {
-                &[
-                    $( stringify!($name) ),*
-                ]
-            }
+macro_rules! __sum_type_parse {
+    // Pull `#[sum_type(...)]` options out of the enum's attributes, normalising
+    // each one into a `(key ...)` group.
+    (@attrs $mode:ident [$($opts:tt)*] [$($attrs:tt)*]
+        #[sum_type( $( $key:ident $( = $value:tt )? $( ( $($args:tt)* ) )? ),* $(,)? )]
+        $($rest:tt)*
+    ) => {
+        $crate::__sum_type_parse!(@attrs $mode
+            [$($opts)* $( ($key $( = $value )? $( ( $($args)* ) )?) )*]
+            [$($attrs)*]
+            $($rest)*);
+    };
+    (@attrs $mode:ident $opts:tt $attrs:tt #[sum_type $($malformed:tt)*] $($rest:tt)*) => {
+        compile_error!(concat!(
+            "Expected `#[sum_type(option, ...)]`, found `#[sum_type",
+            stringify!($($malformed)*),
+            "]`"
+        ));
+    };
+    (@attrs $mode:ident $opts:tt [$($attrs:tt)*] #[$attr:meta] $($rest:tt)*) => {
+        $crate::__sum_type_parse!(@attrs $mode $opts [$($attrs)* #[$attr]] $($rest)*);
+    };
+    (@attrs $mode:ident $opts:tt $attrs:tt pub enum $name:ident { $($variants:tt)* } $($rest:tt)*) => {
+        $crate::__sum_type_parse!(@variants [$mode [pub] $name $opts $attrs] $($variants)*);
+        $crate::__sum_type_parse!(@next $mode $($rest)*);
+    };
+    (@attrs $mode:ident $opts:tt $attrs:tt enum $name:ident { $($variants:tt)* } $($rest:tt)*) => {
+        $crate::__sum_type_parse!(@variants [$mode [] $name $opts $attrs] $($variants)*);
+        $crate::__sum_type_parse!(@next $mode $($rest)*);
+    };
 
-            fn variant(&self) ->  &'static str {
-                match *self {
-                    $(
-                        $enum_name::$name(_) => stringify!($name),
-                    )*
-                }
-            }
+    // Several enums may be declared in one go.
+    (@next $mode:ident) => {};
+    (@next $mode:ident $($rest:tt)+) => {
+        $crate::__sum_type_parse!(@attrs $mode [] [] $($rest)+);
+    };
 
-            fn downcast_ref<T: $crate::_core::any::Any>(&self) -> Option<&T> {
-                use $crate::_core::any::Any;
+    // Split the variants up in one go instead of munching through them, so
+    // big enums don't run into the recursion limit. The attributes are kept
+    // as raw tokens so we can still look inside them, and "lazy" variants
+    // fall back to using their name as the type.
+    (@variants [$mode:ident $vis:tt $name:ident $opts:tt $attrs:tt]
+        $( $( #[$($attr:tt)*] )* $var_name:ident $( ($($var_ty:tt)*) )? $( = $discr:expr )? ),* $(,)?
+    ) => {
+        $( $crate::__sum_type_unsized!(@check $name, $var_name $( ($($var_ty)*) )?); )*
+        $crate::__sum_type_parse!(@split [$mode $vis $name $opts $attrs]
+            [$( { [$( #[$($attr)*] )*] $var_name [$( ($($var_ty)*) )? ($var_name)] [$( = $discr )?] } )*]);
+    };
 
-                match *self {
-                    $(
-                        $enum_name::$name(ref value) => (value as &Any).downcast_ref::<T>(),
-                    )*
-                }
-            }
+    // When every variant only has doc comments and `#[sum_type(...)]`
+    // options there is nothing to strip out or configure away, so the whole
+    // list can be normalised at once.
+    (@split [$mode:ident $vis:tt $name:ident $opts:tt $attrs:tt] [$(
+        { [
+            $( #[doc = $doc:expr] )*
+            $( #[sum_type( $( $key:ident $( = $value:tt )? $( ( $($args:tt)* ) )? ),* $(,)? )] )*
+        ] $var_name:ident [($var_ty:ty) $($lazy:tt)*] [$( = $discr:expr )?] }
+    )*]) => {
+        $crate::__sum_type_parse!(@config [] [] [] [] $opts [$mode $vis $name $opts $attrs] [$(
+            { $var_name($var_ty) [$( #[doc = $doc] )*] [$( #[doc = $doc] )*]
+                [$( $( ($key $( = $value )? $( ( $($args)* ) )?) )* )*] [$( = $discr )?] }
+        )*]);
+    };
+    (@split $header:tt [$($variants:tt)*]) => {
+        $crate::__sum_type_parse!(@variant $header [] [] [] [] [] $($variants)*);
+    };
 
-            fn downcast_mut<T: $crate::_core::any::Any>(&mut self) -> Option<&mut T> {
-                use $crate::_core::any::Any;
+    // Otherwise go through the variants one at a time, keeping track of the
+    // current variant's attributes, doc comments, options and `#[cfg]`s.
+    (@variant [$mode:ident $vis:tt $name:ident $opts:tt $attrs:tt] $variants:tt [] [] [] []) => {
+        $crate::__sum_type_parse!(@config [] [] [] [] $opts [$mode $vis $name $opts $attrs] $variants);
+    };
+    // Doc comments and options, with or without some `#[cfg]`s, are dealt
+    // with in a single step.
+    (@variant $header:tt [$($parsed:tt)*] [$($attrs:tt)*] [$($docs:tt)*] [$($opts:tt)*] []
+        { [
+            $( #[doc = $doc:expr] )*
+            $( #[sum_type( $( $key:ident $( = $value:tt )? $( ( $($args:tt)* ) )? ),* $(,)? )] )*
+        ] $var_name:ident [($var_ty:ty) $($lazy:tt)*] $discr:tt }
+        $($rest:tt)*
+    ) => {
+        $crate::__sum_type_parse!(@variant $header
+            [$($parsed)* { $var_name($var_ty)
+                [$($attrs)* $( #[doc = $doc] )*]
+                [$($docs)* $( #[doc = $doc] )*]
+                [$($opts)* $( $( ($key $( = $value )? $( ( $($args)* ) )?) )* )*]
+                $discr }]
+            [] [] [] [] $($rest)*);
+    };
+    // We can't evaluate a `#[cfg]` ourselves, so a configurable variant
+    // splits the rest of the expansion in two and the compiler throws away
+    // whichever half doesn't apply.
+    (@variant $header:tt [$($parsed:tt)*] [$($attrs:tt)*] [$($docs:tt)*] [$($opts:tt)*] [$( [$($preds:tt)*] )*]
+        { [
+            $( #[doc = $doc:expr] )*
+            $( #[sum_type( $( $key:ident $( = $value:tt )? $( ( $($args:tt)* ) )? ),* $(,)? )] )*
+            $( #[cfg($($pred:tt)*)] )*
+        ] $var_name:ident [($var_ty:ty) $($lazy:tt)*] $discr:tt }
+        $($rest:tt)*
+    ) => {
+        #[cfg(all($( $($preds)*, )* $( $($pred)*, )*))]
+        $crate::__sum_type_parse!(@variant $header
+            [$($parsed)* { $var_name($var_ty)
+                [$($attrs)* $( #[doc = $doc] )* $( #[cfg($($pred)*)] )*]
+                [$($docs)* $( #[doc = $doc] )*]
+                [$($opts)* $( $( ($key $( = $value )? $( ( $($args)* ) )?) )* )*]
+                $discr }]
+            [] [] [] [] $($rest)*);
+        #[cfg(not(all($( $($preds)*, )* $( $($pred)*, )*)))]
+        $crate::__sum_type_parse!(@variant $header [$($parsed)*] [] [] [] [] $($rest)*);
+    };
+    (@variant $header:tt [$($parsed:tt)*] [$($attrs:tt)*] [$($docs:tt)*] [$($opts:tt)*] [$( [$($preds:tt)*] )*]
+        { [
+            $( #[doc = $doc:expr] )*
+            $( #[cfg($($pred:tt)*)] )*
+            $( #[sum_type( $( $key:ident $( = $value:tt )? $( ( $($args:tt)* ) )? ),* $(,)? )] )*
+        ] $var_name:ident [($var_ty:ty) $($lazy:tt)*] $discr:tt }
+        $($rest:tt)*
+    ) => {
+        #[cfg(all($( $($preds)*, )* $( $($pred)*, )*))]
+        $crate::__sum_type_parse!(@variant $header
+            [$($parsed)* { $var_name($var_ty)
+                [$($attrs)* $( #[doc = $doc] )* $( #[cfg($($pred)*)] )*]
+                [$($docs)* $( #[doc = $doc] )*]
+                [$($opts)* $( $( ($key $( = $value )? $( ( $($args)* ) )?) )* )*]
+                $discr }]
+            [] [] [] [] $($rest)*);
+        #[cfg(not(all($( $($preds)*, )* $( $($pred)*, )*)))]
+        $crate::__sum_type_parse!(@variant $header [$($parsed)*] [] [] [] [] $($rest)*);
+    };
+    // Anything else is taken one attribute at a time.
+    (@variant $header:tt $parsed:tt $attrs:tt $docs:tt [$($opts:tt)*] $preds:tt
+        { [#[sum_type( $( $key:ident $( = $value:tt )? $( ( $($args:tt)* ) )? ),* $(,)? )] $($more:tt)*] $($variant:tt)* }
+        $($rest:tt)*
+    ) => {
+        $crate::__sum_type_parse!(@variant $header $parsed $attrs $docs
+            [$($opts)* $( ($key $( = $value )? $( ( $($args)* ) )?) )*]
+            $preds { [$($more)*] $($variant)* } $($rest)*);
+    };
+    (@variant $header:tt $parsed:tt $attrs:tt $docs:tt $opts:tt $preds:tt
+        { [#[sum_type $($malformed:tt)*] $($more:tt)*] $($variant:tt)* }
+        $($rest:tt)*
+    ) => {
+        compile_error!(concat!(
+            "Expected `#[sum_type(option, ...)]`, found `#[sum_type",
+            stringify!($($malformed)*),
+            "]`"
+        ));
+    };
+    (@variant $header:tt $parsed:tt [$($attrs:tt)*] [$($docs:tt)*] $opts:tt $preds:tt
+        { [#[doc = $doc:expr] $($more:tt)*] $($variant:tt)* }
+        $($rest:tt)*
+    ) => {
+        $crate::__sum_type_parse!(@variant $header $parsed
+            [$($attrs)* #[doc = $doc]]
+            [$($docs)* #[doc = $doc]]
+            $opts $preds { [$($more)*] $($variant)* } $($rest)*);
+    };
+    (@variant $header:tt $parsed:tt [$($attrs:tt)*] $docs:tt $opts:tt [$($preds:tt)*]
+        { [#[cfg($($pred:tt)*)] $($more:tt)*] $($variant:tt)* }
+        $($rest:tt)*
+    ) => {
+        $crate::__sum_type_parse!(@variant $header $parsed
+            [$($attrs)* #[cfg($($pred)*)]]
+            $docs $opts [$($preds)* [$($pred)*]] { [$($more)*] $($variant)* } $($rest)*);
+    };
+    // Any other attribute is passed through untouched, finishing the variant
+    // off straight away if that's all it needs.
+    (@variant $header:tt [$($parsed:tt)*] [$($attrs:tt)*] [$($docs:tt)*] [$($opts:tt)*] []
+        { [
+            #[$($attr:tt)*]
+            $( #[doc = $doc:expr] )*
+            $( #[sum_type( $( $key:ident $( = $value:tt )? $( ( $($args:tt)* ) )? ),* $(,)? )] )*
+        ] $var_name:ident [($var_ty:ty) $($lazy:tt)*] $discr:tt }
+        $($rest:tt)*
+    ) => {
+        $crate::__sum_type_parse!(@variant $header
+            [$($parsed)* { $var_name($var_ty)
+                [$($attrs)* #[$($attr)*] $( #[doc = $doc] )*]
+                [$($docs)* $( #[doc = $doc] )*]
+                [$($opts)* $( $( ($key $( = $value )? $( ( $($args)* ) )?) )* )*]
+                $discr }]
+            [] [] [] [] $($rest)*);
+    };
+    (@variant $header:tt $parsed:tt [$($attrs:tt)*] $docs:tt $opts:tt $preds:tt
+        { [#[$($attr:tt)*] $($more:tt)*] $($variant:tt)* }
+        $($rest:tt)*
+    ) => {
+        $crate::__sum_type_parse!(@variant $header $parsed
+            [$($attrs)* #[$($attr)*]]
+            $docs $opts $preds { [$($more)*] $($variant)* } $($rest)*);
+    };
 
-                match *self {
-                    $(
-                        $enum_name::$name(ref mut value) => (value as &mut Any).downcast_mut::<T>(),
-                    )*
-                }
-            }
+    // Work out which attributes need to be added to the generated methods
+    // and impl blocks, and which options change the variants themselves.
+    (@config [$($fn_attrs:tt)*] $impl_attrs:tt $doc_variants:tt $optional:tt [(inline) $($rest:tt)*] $header:tt $variants:tt) => {
+        $crate::__sum_type_parse!(@config [$($fn_attrs)* #[inline]] $impl_attrs $doc_variants $optional [$($rest)*] $header $variants);
+    };
+    (@config $fn_attrs:tt [$($impl_attrs:tt)*] $doc_variants:tt $optional:tt [(impl_attr($( $impl_attr:meta ),* $(,)?)) $($rest:tt)*] $header:tt $variants:tt) => {
+        $crate::__sum_type_parse!(@config $fn_attrs [$($impl_attrs)* $( #[$impl_attr] )*] $doc_variants $optional [$($rest)*] $header $variants);
+    };
+    (@config $fn_attrs:tt $impl_attrs:tt $doc_variants:tt $optional:tt [(doc_variants) $($rest:tt)*] $header:tt $variants:tt) => {
+        $crate::__sum_type_parse!(@config $fn_attrs $impl_attrs [doc_variants] $optional [$($rest)*] $header $variants);
+    };
+    (@config $fn_attrs:tt $impl_attrs:tt $doc_variants:tt $optional:tt [(optional) $($rest:tt)*] $header:tt $variants:tt) => {
+        $crate::__sum_type_parse!(@config $fn_attrs $impl_attrs $doc_variants [optional] [$($rest)*] $header $variants);
+    };
+    (@config $fn_attrs:tt $impl_attrs:tt $doc_variants:tt $optional:tt [$other:tt $($rest:tt)*] $header:tt $variants:tt) => {
+        $crate::__sum_type_parse!(@config $fn_attrs $impl_attrs $doc_variants $optional [$($rest)*] $header $variants);
+    };
+    (@config $fn_attrs:tt $impl_attrs:tt $doc_variants:tt $optional:tt [] $header:tt $variants:tt) => {
+        $crate::__sum_type_parse!(@docs $fn_attrs $impl_attrs $header $doc_variants $optional $variants);
+    };
 
-            fn variant_is<T: $crate::_core::any::Any>(&self) -> bool {
-                self.downcast_ref::<T>().is_some()
-            }
+    // `doc_variants` waits until now so it only lists the variants which
+    // were compiled in, but runs before `optional` so it shows the payload
+    // types as they were written.
+    (@docs $fn_attrs:tt [$( #[$impl_attr:meta] )*] [$mode:ident $vis:tt $name:ident $opts:tt [$($attrs:tt)*]]
+        [doc_variants] $optional:tt
+        [$( { $var_name:ident($var_ty:ty) $var_attrs:tt $var_docs:tt $var_opts:tt $var_discr:tt } )*]
+    ) => {
+        $( #[$impl_attr] )*
+        impl $name {
+            /// The list of variants which `doc_variants` appended to this
+            /// type's documentation.
+            pub const VARIANTS_DOC: &'static str =
+                $crate::__sum_type_doc_variants!($( $crate::__sum_type_label!($var_name $var_opts) => $var_ty ),*);
         }
-    }
-}
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! __assert_multiple_variants {
-    ($enum_name:ident, $name:ident => $variant_type:ty) => {
-        compile_error!(concat!(
-            "The `",
-            stringify!($enum_name),
-            "` type must have more than one variant"
-        ));
+        $crate::__sum_type_parse!(@optional $fn_attrs [$( #[$impl_attr] )*]
+            [$mode $vis $name $opts [
+                $($attrs)*
+                #[doc = ""]
+                #[doc = $crate::__sum_type_doc_variants!($( $crate::__sum_type_label!($var_name $var_opts) => $var_ty ),*)]
+            ]]
+            $optional
+            [$( { $var_name($var_ty) $var_attrs $var_docs $var_opts $var_discr } )*]);
+    };
+    (@docs $fn_attrs:tt $impl_attrs:tt $header:tt [] $optional:tt $variants:tt) => {
+        $crate::__sum_type_parse!(@optional $fn_attrs $impl_attrs $header $optional $variants);
     };
-    ($enum_name:ident, $( $name:ident => $variant_type:ty ),*) => {};
-}
 
-#[doc(hidden)]
-#[macro_export]
-macro_rules! __sum_type_impls {
-    ($enum_name:ident, $( $name:ident => $variant_type:ty ),*) => (
-        $crate::__assert_multiple_variants!($enum_name, $( $name => $variant_type ),*);
+    (@optional $fn_attrs:tt $impl_attrs:tt $header:tt [optional]
+        [$( { $var_name:ident($var_ty:ty) $var_attrs:tt $var_docs:tt $var_opts:tt $var_discr:tt } )*]
+    ) => {
+        $crate::__sum_type_parse!(@generate $fn_attrs $impl_attrs $header
+            [$( { $var_name($crate::_core::option::Option<$var_ty>) $var_attrs $var_docs $var_opts $var_discr } )*]);
+    };
+    (@optional $fn_attrs:tt $impl_attrs:tt $header:tt [] $variants:tt) => {
+        $crate::__sum_type_parse!(@generate $fn_attrs $impl_attrs $header $variants);
+    };
 
-        $crate::__sum_type_from!($enum_name, $($name => $variant_type),*);
-        $crate::__sum_type_try_from!($enum_name, $($name => $variant_type),*);
-        $crate::__sum_type_trait!($enum_name, $($name => $variant_type),*);
-    )
+    (@generate $fn_attrs:tt $impl_attrs:tt
+        [$mode:ident [$($vis:tt)*] $name:ident $opts:tt $attrs:tt]
+        [$( { $var_name:ident($var_ty:ty) $var_attrs:tt $var_docs:tt $var_opts:tt $var_discr:tt } )*]
+    ) => {
+        $crate::__sum_type_enum!($mode, $attrs, [$($vis)*], $name, [$( $var_name($var_ty) $var_attrs ),*]);
+
+        $crate::__sum_type_impls!($name, $fn_attrs, $impl_attrs, $( $var_name ($crate::__sum_type_label!($var_name $var_opts)) => $var_ty ),*);
+        $( $crate::__sum_type_conversions!(@mode $opts, $var_opts, $opts, $name, $fn_attrs, $impl_attrs, $var_name ($crate::__sum_type_label!($var_name $var_opts)) => $var_ty); )*
+        $crate::__sum_type_options!($name, [$($vis)*], $fn_attrs, $impl_attrs, $opts, [$( $var_name ($crate::__sum_type_label!($var_name $var_opts)) => $var_ty ),*]);
+        $( $crate::__sum_type_variant_options!($name, $opts, $fn_attrs, $impl_attrs, $var_name ($crate::__sum_type_label!($var_name $var_opts)) => $var_ty, $var_opts); )*
+        $crate::__sum_type_result!(@scan $name, $fn_attrs, $impl_attrs, [] [], [$( { $var_opts $var_name ($var_ty) } )*]);
+        $crate::__sum_type_serde_via_repr!(@find $opts, $name, $fn_attrs, $impl_attrs, [$( { $var_name ($var_ty) $var_opts } )*]);
+        $crate::__sum_type_bytes!(@scan $name, $fn_attrs, $impl_attrs, [], [$( { $var_opts $var_name } )*]);
+        $crate::__sum_type_variant_meta!(@find $opts, $name, $fn_attrs, $impl_attrs, [$( $var_name ($crate::__sum_type_label!($var_name $var_opts)) $var_docs => $var_ty ),*]);
+        $crate::__sum_type_kind!(@find $opts, $opts, [$($vis)*], $name, $fn_attrs, $impl_attrs, [$( $var_name $var_docs ($crate::__sum_type_label!($var_name $var_opts)) $var_discr ),*]);
+    };
 }
 
-/// The entire point.
+/// Emit the enum definition itself, unless it was declared separately and
+/// only the impls are wanted.
+#[doc(hidden)]
 #[macro_export]
-macro_rules! sum_type {
+macro_rules! __sum_type_enum {
     (
-        $( #[$outer:meta] )*
-        pub enum $name:ident {
-            $(
-                $( #[$inner:meta] )*
-                $var_name:ident($var_ty:ty),
-                )*
-        }) => {
-       $( #[$outer] )*
-        pub enum $name {
+        define,
+        [$( #[$outer:meta] )*],
+        [$($vis:tt)*],
+        $name:ident,
+        [$( $var_name:ident($var_ty:ty) [$( #[$inner:meta] )*] ),*]
+    ) => {
+        $( #[$outer] )*
+        $($vis)* enum $name {
             $(
                 $( #[$inner] )*
                 $var_name($var_ty),
             )*
         }
+    };
+    (derive, $($rest:tt)*) => {};
+}
 
-        $crate::__sum_type_impls!($name, $( $var_name => $var_ty),*);
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_options {
+    ($enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$($opt:tt)*], $variants:tt) => {
+        $( $crate::__sum_type_option!($opt, $enum_name, $vis, $fn_attrs, $impl_attrs, $variants); )*
     };
-    (
-        $( #[$outer:meta] )*
-        enum $name:ident {
-            $(
-                $( #[$inner:meta] )*
-                $var_name:ident($var_ty:ty),
-                )*
-        }) => {
-       $( #[$outer] )*
-        enum $name {
-            $(
-                $( #[$inner] )*
-                $var_name($var_ty),
-            )*
-        }
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_option {
+    // options which were already applied while parsing
+    ((inline), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {};
+    ((kind = $kind:ident), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {};
+    ((impl_attr($($attrs:tt)*)), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {};
+    ((doc_variants), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {};
+    ((repr_kind_from_primitive), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {};
+    ((kind_set = $set:ident), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {};
+    ((from_variant_attr), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {};
+    ((serde_via_struct), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {};
+    ((variants_meta), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {};
+    ((partial_eq_option), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {};
+
+    ((try_sum), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_try_fold!(try_sum, Add, +, $enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((try_product), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_try_fold!(try_product, Mul, *, $enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((try_add_assign), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_try_assign!(try_add_assign, AddAssign, +=, $enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((neg), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_neg!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((from_str), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_from_str!(eq, $enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((ignore_case_from_str), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_from_str!(eq_ignore_ascii_case, $enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((partial_eq_str), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {
+        $crate::__sum_type_partial_eq_str!($enum_name, $fn_attrs, $impl_attrs);
+    };
+    ((checked_index), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_from_index_default!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((from_ref), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_from_ref!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((optional), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_optional!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((try_from_ref_clone), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_try_from_ref_clone!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((reset_payload), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_reset_payload!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((debug_dyn), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_as_debug!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((hash), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_hash!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((payloads_eq), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_payloads_eq!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((ord_by_payload), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_ord_by_payload!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((eq_by_variant_only), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_eq_by_variant_only!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((option_pair), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_option_pair!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((tryfrom_by_index), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_payload_if_index!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((into_iter), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_into_iter!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((serde_untagged), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_serde_untagged!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((json), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_json!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((truthy($( $truthy:ident ),+ $(,)?)), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {
+        $crate::__sum_type_truthy!($enum_name, $fn_attrs, $impl_attrs, [$( $truthy ),+]);
+    };
+    ((fmt_radix), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_fmt_delegate!(Binary, $enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+        $crate::__sum_type_fmt_delegate!(Octal, $enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+        $crate::__sum_type_fmt_delegate!(LowerHex, $enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+        $crate::__sum_type_fmt_delegate!(UpperHex, $enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((debug_indexed), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_debug_indexed!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((c_repr_tag = $c_repr:ident), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_c_repr!($vis $c_repr, $enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((variant_key = $key:ident), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {
+        $crate::__sum_type_variant_key!($vis $key, $enum_name, $fn_attrs, $impl_attrs);
+    };
+    ((builder = $builder:ident), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {
+        $crate::__sum_type_builder!($vis $builder, $enum_name, $fn_attrs, $impl_attrs);
+    };
+    ((exhaustive_match_helper), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {
+        $crate::__sum_type_match_helper!(match_all, $enum_name, $);
+    };
+    ((exhaustive_match_helper = $helper:ident), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {
+        $crate::__sum_type_match_helper!($helper, $enum_name, $);
+    };
+    ((retag), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_retag!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((fold_ref), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_fold_ref!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((collapse), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_collapse!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((variant_index_of_name), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_variant_index_of_name!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((variant_table), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_variant_table!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((to_variant_index_map), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_to_variant_index_map!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((is_unit_variant), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_is_unit_variant!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((payload_size), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_payload_size!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((as_any), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_as_any!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    ((into_parts), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, [$( $name:ident ($label:expr) => $variant_type:ty ),*]) => {
+        $crate::__sum_type_into_parts!($enum_name, $fn_attrs, $impl_attrs, $( $name ($label) => $variant_type ),*);
+    };
+    (($option:ident $($tokens:tt)*), $enum_name:ident, $vis:tt, $fn_attrs:tt, $impl_attrs:tt, $variants:tt) => {
+        compile_error!(concat!(
+            "Unknown option, `",
+            stringify!($option $($tokens)*),
+            "`, for the `",
+            stringify!($enum_name),
+            "` sum type"
+        ));
+    };
+}
 
-        $crate::__sum_type_impls!($name, $( $var_name => $var_ty),*);
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_variant_options {
+    ($enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty, [$($opt:tt)*]) => {
+        $( $crate::__sum_type_variant_option!($opt, $enum_name, $enum_opts, $fn_attrs, $impl_attrs, $name ($label) => $variant_type); )*
     };
+}
 
-    // "lazy" variations which reuse give the variant the same name as its type.
-    (
-        $( #[$outer:meta] )*
-        pub enum $name:ident {
-            $(
-                $( #[$inner:meta] )*
-                $var_name:ident,
-                )*
-        }) => {
-            $crate::sum_type!($(#[$outer])* pub enum $name { $( $(#[$inner])* $var_name($var_name), )* });
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __sum_type_variant_option {
+    // options which were already applied while parsing
+    ((rename = $new_name:tt), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {};
+    ((skip_from), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {};
+    ((from), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {};
+    ((bytes), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {};
+    ((ok), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {};
+    ((err), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {};
+
+    ((copy = $method:ident), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {
+        $crate::__sum_type_copy_accessor!($enum_name, $fn_attrs, $impl_attrs, $method, $name ($label) => $variant_type);
     };
-    (
-        $( #[$outer:meta] )*
-        enum $name:ident {
-            $(
-                $( #[$inner:meta] )*
-                $var_name:ident($var_ty:ty),
-                )*
-        }) => {
-            $crate::sum_type!($(#[$outer])* enum $name { $( $(#[$inner])* $var_name($var_name), )* });
+    ((flatten($( $leaf_type:ty ),* $(,)?)), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {
+        $( $crate::__sum_type_flatten!($enum_name, $fn_attrs, $impl_attrs, $leaf_type, $name ($label) => $variant_type); )*
+    };
+    ((box_ctor = $method:ident), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {
+        $crate::__sum_type_box_ctor!($enum_name, $fn_attrs, $impl_attrs, $method, $name ($label) => $variant_type);
+    };
+    ((try_map = $method:ident), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {
+        $crate::__sum_type_try_map!($enum_name, $fn_attrs, $impl_attrs, $method, $name ($label) => $variant_type);
+    };
+    ((zip = $method:ident), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {
+        $crate::__sum_type_zip!($enum_name, $fn_attrs, $impl_attrs, $method, $name ($label) => $variant_type);
+    };
+    ((from_iter), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {
+        $crate::__sum_type_from_iter!($enum_name, $fn_attrs, $impl_attrs, $name ($label) => $variant_type);
+    };
+    ((builder = $setter:ident), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {
+        $crate::__sum_type_builder!(@setter $enum_opts, $enum_name, $fn_attrs, $impl_attrs, $setter, $name ($label) => $variant_type);
+    };
+    (($option:ident $($tokens:tt)*), $enum_name:ident, $enum_opts:tt, $fn_attrs:tt, $impl_attrs:tt, $name:ident ($label:expr) => $variant_type:ty) => {
+        compile_error!(concat!(
+            "Unknown option, `",
+            stringify!($option $($tokens)*),
+            "`, for the `",
+            stringify!($enum_name),
+            "::",
+            stringify!($name),
+            "` variant"
+        ));
     };
 }
 
@@ -462,6 +6677,38 @@ macro_rules! sum_type {
 ///
 /// sum_type::defer!(Foo as first; Second | Third => |ref _dont_care| ());
 /// ```
+///
+/// The expression being deferred to is only evaluated once, even when it has
+/// side effects and the variant isn't handled.
+///
+/// ```rust
+/// use std::cell::Cell;
+/// use std::panic;
+///
+/// sum_type::sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Foo {
+///         First(u32),
+///         Second(f64),
+///     }
+/// }
+///
+/// let calls = Cell::new(0);
+/// let next = || {
+///     calls.set(calls.get() + 1);
+///     Foo::First(42)
+/// };
+///
+/// let got = sum_type::defer!(Foo as next(); First | Second => |ref item| item.to_string());
+/// assert_eq!(got, "42");
+/// assert_eq!(calls.get(), 1);
+///
+/// let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+///     sum_type::defer!(Foo as next(); Second => |ref _dont_care| ())
+/// }));
+/// assert!(result.is_err());
+/// assert_eq!(calls.get(), 2);
+/// ```
 #[macro_export]
 macro_rules! defer {
     ($kind:ident as $variable:expr; $( $variant:ident )|* => |ref $item:ident| $exec:expr) => {
@@ -483,19 +6730,122 @@ macro_rules! defer {
             $(
                 $pattern => $exec,
             )*
+            // reuse the value being matched on so $variable is only evaluated once
             #[allow(unreachable_patterns)]
-            _ => unreachable!("Unexpected variant, {}, for {}",
-                <_ as $crate::SumType>::variant(&$variable),
+            ref other => unreachable!("Unexpected variant, {}, for {}",
+                <_ as $crate::SumType>::variant(other),
                 stringify!($kind)),
         }
     }
 }
 
+/// Run a [`defer!()`] operation over every item in a list of sum types.
+///
+/// The items may be sum types or references to them, and the results are
+/// collected into any container implementing `FromIterator` (e.g. a `Vec`).
+///
+/// # Examples
+///
+/// ```rust
+/// sum_type::sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Foo {
+///         First(u32),
+///         Second(f64),
+///         Third(String),
+///     }
+/// }
+///
+/// let items = vec![
+///     Foo::First(42),
+///     Foo::Second(1.5),
+///     Foo::Third(String::from("Hello World")),
+/// ];
+///
+/// let got: Vec<String> = sum_type::defer_map!(Foo as &items;
+///     First | Second | Third => |ref item| item.to_string());
+/// assert_eq!(got, vec!["42", "1.5", "Hello World"]);
+///
+/// // the list can also be consumed
+/// let lengths: Vec<usize> = sum_type::defer_map!(Foo as items;
+///     First | Second | Third => |ref item| item.to_string().len());
+/// assert_eq!(lengths, vec![2, 3, 11]);
+/// ```
+///
+/// [`defer!()`]: macro.defer.html
+#[macro_export]
+macro_rules! defer_map {
+    ($kind:ident as $items:expr; $( $variant:ident )|* => |ref $item:ident| $exec:expr) => {
+        $crate::_core::iter::Iterator::collect(
+            $crate::_core::iter::Iterator::map(
+                $crate::_core::iter::IntoIterator::into_iter($items),
+                |value| {
+                    let value: &$kind = $crate::_core::borrow::Borrow::borrow(&value);
+                    $crate::defer!($kind as *value; $( $variant )|* => |ref $item| $exec)
+                },
+            ),
+        )
+    };
+}
+
+/// Convert between two sum types by transforming each variant's payload.
+///
+/// This expands to a closure which matches on each variant of the first sum
+/// type, passes its payload to the corresponding function, and wraps the
+/// result in the second sum type's variant with the same name. Every variant
+/// must be handled, so forgetting one is a compile error.
+///
+/// # Examples
+///
+/// ```rust
+/// sum_type::sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Small {
+///         First(u32),
+///         Second(f32),
+///         Third(String),
+///     }
+/// }
+///
+/// sum_type::sum_type! {
+///     #[derive(Debug, PartialEq)]
+///     pub enum Big {
+///         First(u64),
+///         Second(f64),
+///         Third(usize),
+///     }
+/// }
+///
+/// let widen = sum_type::sum_type_map!(Small => Big;
+///     First => u64::from,
+///     Second => f64::from,
+///     Third => |s: String| s.len(),
+/// );
+///
+/// assert_eq!(widen(Small::First(42)), Big::First(42));
+/// assert_eq!(widen(Small::Second(1.5)), Big::Second(1.5));
+/// assert_eq!(widen(Small::Third(String::from("Hello"))), Big::Third(5));
+/// ```
+#[macro_export]
+macro_rules! sum_type_map {
+    ($from:ident => $to:ident; $( $variant:ident => $func:expr ),* $(,)?) => {
+        |value: $from| -> $to {
+            match value {
+                $(
+                    $from::$variant(item) => $to::$variant(($func)(item)),
+                )*
+            }
+        }
+    };
+}
+
 /// An example of the generated sum type.
 #[cfg(feature = "generated_example")]
 #[allow(missing_docs)]
 pub mod generated_example {
     sum_type! {
+        #[sum_type(variant_table, variant_index_of_name, to_variant_index_map, variants_meta, as_any)]
+        #[sum_type(is_unit_variant, payload_size, fold_ref, collapse, retag)]
         #[derive(Debug, Copy, Clone, PartialEq)]
         pub enum MySumType {
             /// The first variant.
@@ -0,0 +1,336 @@
+//! A `#[derive(SumType)]` front-end for the [`sum_type`] crate.
+//!
+//! You probably want to enable `sum_type`'s `derive` feature and use the
+//! re-export from there instead of depending on this crate directly.
+//!
+//! [`sum_type`]: https://docs.rs/sum_type
+
+#![deny(missing_docs, missing_debug_implementations, unsafe_code)]
+
+extern crate proc_macro;
+
+use proc_macro2::{Delimiter, Spacing, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
+use syn::{Data, DeriveInput, Error, Fields, Ident, Type};
+
+/// Implement `SumType`, `From` and `TryFrom` for an enum where every variant
+/// holds exactly one value.
+///
+/// Adding `#[sum_type(clone)]` also implements `Clone`, bounded on the
+/// payload types rather than on every type parameter.
+#[proc_macro_derive(SumType, attributes(sum_type))]
+pub fn derive_sum_type(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    expand(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[derive(Default)]
+struct Options {
+    clone: bool,
+}
+
+fn options(input: &DeriveInput) -> Result<Options, Error> {
+    let mut options = Options::default();
+
+    for attr in &input.attrs {
+        if !attr.path().is_ident("sum_type") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("clone") {
+                options.clone = true;
+                Ok(())
+            } else {
+                Err(meta.error("Unknown sum_type option"))
+            }
+        })?;
+    }
+
+    Ok(options)
+}
+
+struct Variant<'a> {
+    name: &'a Ident,
+    ty: &'a Type,
+}
+
+fn variants(input: &DeriveInput) -> Result<Vec<Variant<'_>>, Error> {
+    let data = match input.data {
+        Data::Enum(ref data) => data,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "A SumType can only be derived for enums",
+            ))
+        }
+    };
+
+    if data.variants.len() < 2 {
+        return Err(Error::new_spanned(
+            &input.ident,
+            format!("The `{}` type must have more than one variant", input.ident),
+        ));
+    }
+
+    data.variants
+        .iter()
+        .map(|variant| match variant.fields {
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => Ok(Variant {
+                name: &variant.ident,
+                ty: &fields.unnamed[0].ty,
+            }),
+            _ => Err(Error::new_spanned(
+                variant,
+                "Each variant must hold exactly one unnamed field",
+            )),
+        })
+        .collect()
+}
+
+/// Is this type one of the enum's own type parameters (e.g. the `T` in
+/// `Either<T, U>`)? The `From` impls for `T` and `U` would overlap, and the
+/// orphan rules forbid `impl TryFrom<Either<T, U>> for T`.
+fn is_type_param(input: &DeriveInput, ty: &Type) -> bool {
+    let ident = match *ty {
+        Type::Path(ref path) if path.qself.is_none() => match path.path.get_ident() {
+            Some(ident) => ident,
+            None => return false,
+        },
+        _ => return false,
+    };
+
+    input.generics.type_params().any(|param| param.ident == *ident)
+}
+
+/// Print a type the way `stringify!()` does in `sum_type!()`, so error
+/// messages read the same with either front-end (`Vec<u8>` rather than the
+/// `Vec < u8 >` you get from `TokenStream`'s `Display` impl).
+fn type_name(ty: &Type) -> String {
+    let mut name = String::new();
+    write_tokens(ty.to_token_stream(), &mut name);
+    name
+}
+
+fn write_tokens(tokens: TokenStream, out: &mut String) {
+    // was the last thing written an identifier, keyword or literal?
+    let mut after_word = false;
+    // did we just write the space after a `,` or `;`?
+    let mut after_separator = false;
+    // multi-character operators like `->` arrive one joint `Punct` at a time
+    let mut op = String::new();
+
+    for token in tokens {
+        if let TokenTree::Punct(ref punct) = token {
+            op.push(punct.as_char());
+            if punct.spacing() == Spacing::Joint {
+                continue;
+            }
+        } else if !op.is_empty() {
+            // a lifetime's `'` is joint with the identifier after it
+            out.push_str(&op);
+            op.clear();
+            after_word = false;
+        }
+
+        after_separator = false;
+
+        match token {
+            TokenTree::Ident(_) | TokenTree::Literal(_) => {
+                if after_word {
+                    out.push(' ');
+                }
+                out.push_str(&token.to_string());
+                after_word = true;
+            }
+            TokenTree::Punct(_) => {
+                match op.as_str() {
+                    "->" | "=>" | "+" | "=" => {
+                        out.push(' ');
+                        out.push_str(&op);
+                        out.push(' ');
+                    }
+                    "," | ";" => {
+                        out.push_str(&op);
+                        out.push(' ');
+                        after_separator = true;
+                    }
+                    _ => out.push_str(&op),
+                }
+                // `for<'a> Fn(&'a u8)` keeps the space after the `>`
+                after_word = op == ">";
+                op.clear();
+            }
+            TokenTree::Group(ref group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => ("", ""),
+                };
+                out.push_str(open);
+                write_tokens(group.stream(), out);
+                out.push_str(close);
+                after_word = false;
+            }
+        }
+    }
+
+    out.push_str(&op);
+
+    // `(u32,)` rather than `(u32, )`
+    if after_separator {
+        out.pop();
+    }
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream, Error> {
+    let options = options(input)?;
+    let variants = variants(input)?;
+    let enum_name = &input.ident;
+    let names: Vec<_> = variants.iter().map(|v| v.name).collect();
+    let types: Vec<_> = variants.iter().map(|v| v.ty).collect();
+    let labels: Vec<_> = names.iter().map(|name| name.to_string()).collect();
+    let type_names: Vec<_> = types.iter().map(|ty| type_name(ty)).collect();
+    let indices: Vec<_> = (0..variants.len()).collect();
+
+    // Downcasting goes through `Any`, so every payload needs to be `'static`
+    let mut any_generics = input.generics.clone();
+    {
+        let where_clause = any_generics.make_where_clause();
+        for ty in &types {
+            where_clause.predicates.push(syn::parse_quote!(#ty: 'static));
+        }
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (_, _, any_where_clause) = any_generics.split_for_impl();
+
+    let mut tokens = quote! {
+        impl #impl_generics ::sum_type::__private::Sealed for #enum_name #ty_generics #where_clause {}
+
+        impl #impl_generics ::sum_type::SumType for #enum_name #ty_generics #any_where_clause {
+            fn variants(&self) -> &'static [&'static str] {
+                &[ #( #labels ),* ]
+            }
+
+            fn variant(&self) -> &'static str {
+                match *self {
+                    #( #enum_name::#names(_) => #labels, )*
+                }
+            }
+
+            fn variant_index(&self) -> usize {
+                match *self {
+                    #( #enum_name::#names(_) => #indices, )*
+                }
+            }
+
+            fn payload_type_id(&self) -> ::sum_type::_core::any::TypeId {
+                match *self {
+                    #( #enum_name::#names(_) => ::sum_type::_core::any::TypeId::of::<#types>(), )*
+                }
+            }
+
+            fn downcast_ref<__T: ::sum_type::_core::any::Any>(&self) -> ::sum_type::_core::option::Option<&__T> {
+                match *self {
+                    #( #enum_name::#names(ref value) => (value as &dyn ::sum_type::_core::any::Any).downcast_ref::<__T>(), )*
+                }
+            }
+
+            fn downcast_mut<__T: ::sum_type::_core::any::Any>(&mut self) -> ::sum_type::_core::option::Option<&mut __T> {
+                match *self {
+                    #( #enum_name::#names(ref mut value) => (value as &mut dyn ::sum_type::_core::any::Any).downcast_mut::<__T>(), )*
+                }
+            }
+
+            fn variant_is<__T: ::sum_type::_core::any::Any>(&self) -> bool {
+                self.downcast_ref::<__T>().is_some()
+            }
+
+            fn __variant_for_type(
+                &self,
+                type_id: ::sum_type::_core::any::TypeId,
+            ) -> ::sum_type::_core::option::Option<(&'static str, &'static str)> {
+                #(
+                    if type_id == ::sum_type::_core::any::TypeId::of::<#types>() {
+                        return ::sum_type::_core::option::Option::Some((#labels, #type_names));
+                    }
+                )*
+
+                ::sum_type::_core::option::Option::None
+            }
+        }
+    };
+
+    if options.clone {
+        // unlike #[derive(Clone)], only require what the payloads need
+        let mut clone_generics = input.generics.clone();
+        {
+            let where_clause = clone_generics.make_where_clause();
+            for ty in &types {
+                where_clause
+                    .predicates
+                    .push(syn::parse_quote!(#ty: ::sum_type::_core::clone::Clone));
+            }
+        }
+        let (_, _, clone_where_clause) = clone_generics.split_for_impl();
+
+        tokens.extend(quote! {
+            impl #impl_generics ::sum_type::_core::clone::Clone for #enum_name #ty_generics #clone_where_clause {
+                fn clone(&self) -> Self {
+                    match *self {
+                        #( #enum_name::#names(ref value) => #enum_name::#names(::sum_type::_core::clone::Clone::clone(value)), )*
+                    }
+                }
+            }
+        });
+    }
+
+    for variant in &variants {
+        let name = variant.name;
+        let ty = variant.ty;
+        let label = name.to_string();
+        let expected_type = type_name(ty);
+
+        if is_type_param(input, ty) {
+            continue;
+        }
+
+        tokens.extend(quote! {
+            impl #impl_generics ::sum_type::_core::convert::From<#ty> for #enum_name #ty_generics #where_clause {
+                fn from(other: #ty) -> Self {
+                    #enum_name::#name(other)
+                }
+            }
+
+            // no SumType here, so borrowed payloads don't need to be 'static
+            impl #impl_generics ::sum_type::_core::convert::TryFrom<#enum_name #ty_generics> for #ty #where_clause {
+                type Error = ::sum_type::InvalidType;
+
+                fn try_from(other: #enum_name #ty_generics) -> ::sum_type::_core::result::Result<Self, Self::Error> {
+                    let variant = match other {
+                        #( #enum_name::#names(_) => #labels, )*
+                    };
+
+                    if let #enum_name::#name(value) = other {
+                        ::sum_type::_core::result::Result::Ok(value)
+                    } else {
+                        ::sum_type::_core::result::Result::Err(::sum_type::InvalidType {
+                            expected_variant: #label,
+                            expected_type: #expected_type,
+                            actual_variant: variant,
+                            all_variants: &[ #( #labels ),* ],
+                            __non_exhaustive: (),
+                        })
+                    }
+                }
+            }
+        });
+    }
+
+    Ok(tokens)
+}
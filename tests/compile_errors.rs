@@ -0,0 +1,206 @@
+//! Make sure the errors which are meant to point people in the right
+//! direction actually say what we think they do.
+//!
+//! Each case is compiled as its own little crate and we look at the first
+//! error `cargo check` reports. Older compilers put a constant's panic
+//! message below the error's headline, so we check the whole diagnostic.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn first_error(name: &str, source: &str) -> String {
+    let dependency = format!("sum_type = {{ path = {:?} }}", env!("CARGO_MANIFEST_DIR"));
+    first_error_with(name, &dependency, source)
+}
+
+fn first_error_with(name: &str, dependency: &str, source: &str) -> String {
+    let root = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("compile_errors");
+    let dir = root.join(name);
+    fs::create_dir_all(dir.join("src")).unwrap();
+
+    let manifest = format!(
+        "[package]\nname = {:?}\nversion = \"0.0.0\"\nedition = \"2018\"\n\n\
+         [dependencies]\n{}\n\n[workspace]\n",
+        name, dependency,
+    );
+    fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+    fs::write(dir.join("src").join("lib.rs"), source).unwrap();
+
+    let output = Command::new(env!("CARGO"))
+        .args(["check", "--quiet", "--offline"])
+        .env("CARGO_TARGET_DIR", root.join("target"))
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "{} compiled successfully", name);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let start = match stderr.find("error") {
+        Some(start) => start,
+        None => panic!("no errors found in:\n{}", stderr),
+    };
+    let diagnostic = &stderr[start..];
+    // each diagnostic ends with a blank line
+    let end = diagnostic.find("\n\n").unwrap_or(diagnostic.len());
+
+    diagnostic[..end].to_string()
+}
+
+fn assert_mentions(diagnostic: &str, message: &str) {
+    assert!(
+        diagnostic.contains(message),
+        "expected {:?} in:\n{}",
+        message,
+        diagnostic
+    );
+}
+
+#[test]
+fn unsized_str_payload() {
+    let error = first_error(
+        "unsized_str_payload",
+        "sum_type::sum_type! { pub enum Unsized { Text(str), Number(u32) } }",
+    );
+
+    assert_mentions(
+        &error,
+        "The `Text` variant of `Unsized` can't hold a `str` directly because it is \
+         unsized, use something like `&'static str` or a `Box<str>` instead"
+    );
+}
+
+#[test]
+fn unsized_slice_payload() {
+    let error = first_error(
+        "unsized_slice_payload",
+        "sum_type::sum_type! { pub enum Unsized { Bytes([u8]), Number(u32) } }",
+    );
+
+    assert_mentions(
+        &error,
+        "The `Bytes` variant of `Unsized` can't hold a `[u8]` directly because it is \
+         unsized, use something like `&'static [u8]` or a `Box<[u8]>` instead"
+    );
+}
+
+#[test]
+fn unsized_trait_object_payload() {
+    let error = first_error(
+        "unsized_trait_object_payload",
+        "sum_type::sum_type! { pub enum Unsized { Debug(dyn std::fmt::Debug), Number(u32) } }",
+    );
+
+    assert_mentions(
+        &error,
+        "The `Debug` variant of `Unsized` can't hold a `dyn std::fmt::Debug` directly \
+         because it is unsized, use something like a `Box<dyn std::fmt::Debug>` instead"
+    );
+}
+
+#[test]
+fn ord_by_payload_with_mixed_types() {
+    let error = first_error(
+        "ord_by_payload_with_mixed_types",
+        "sum_type::sum_type! {
+            #[sum_type(ord_by_payload)]
+            pub enum Mixed { Number(u32), Text(String) }
+        }",
+    );
+
+    assert_mentions(
+        &error,
+        "The `ord_by_payload` option on `Mixed` requires \
+         every variant to hold the same type, written the same way"
+    );
+}
+
+#[test]
+fn into_parts_without_alloc() {
+    let error = first_error(
+        "into_parts_without_alloc",
+        "sum_type::sum_type! {
+            #[sum_type(into_parts)]
+            pub enum Parts { Number(u32), Text(&'static str) }
+        }",
+    );
+
+    assert_mentions(
+        &error,
+        "The `into_parts` option on `Parts` requires the `alloc` feature of `sum_type`"
+    );
+}
+
+#[test]
+fn kind_set_with_too_many_variants() {
+    // arrays of different lengths, so every variant has its own type
+    let variants: Vec<String> = (0..65).map(|i| format!("V{0}([u8; {0}])", i)).collect();
+    let source = format!(
+        "sum_type::sum_type! {{
+            #[sum_type(kind = WideKind, kind_set = WideKindSet)]
+            pub enum Wide {{ {} }}
+        }}",
+        variants.join(", "),
+    );
+    let error = first_error("kind_set_with_too_many_variants", &source);
+
+    assert_mentions(
+        &error,
+        "The `kind_set` option on `Wide` only supports sum types with at most 64 variants"
+    );
+}
+
+#[test]
+fn serde_untagged_with_renamed_dependency() {
+    // serde's derive names us by a string path, which is documented as needing
+    // `sum_type` to be a direct dependency under its own name
+    let dependency = format!(
+        "renamed = {{ package = \"sum_type\", path = {:?}, features = [\"serde\"] }}",
+        env!("CARGO_MANIFEST_DIR"),
+    );
+    let error = first_error_with(
+        "serde_untagged_with_renamed_dependency",
+        &dependency,
+        "renamed::sum_type! {
+            #[sum_type(serde_untagged)]
+            pub enum Untagged { Number(u32), Text(String) }
+        }",
+    );
+
+    assert_mentions(&error, "unresolved import `sum_type`");
+}
+
+#[test]
+fn serde_via_struct_with_renamed_dependency() {
+    let dependency = format!(
+        "renamed = {{ package = \"sum_type\", path = {:?}, features = [\"serde\"] }}",
+        env!("CARGO_MANIFEST_DIR"),
+    );
+    let error = first_error_with(
+        "serde_via_struct_with_renamed_dependency",
+        &dependency,
+        "renamed::sum_type! {
+            #[sum_type(serde_via_struct)]
+            pub enum Tagged { Number(u32), Text(String) }
+        }",
+    );
+
+    assert_mentions(&error, "unresolved import `sum_type`");
+}
+
+#[test]
+fn discriminants_without_kind() {
+    let error = first_error(
+        "discriminants_without_kind",
+        "sum_type::sum_type! {
+            pub enum Tagged { Number(u32) = 1, Text(&'static str) = 2 }
+        }",
+    );
+
+    assert_mentions(
+        &error,
+        "Variants of `Tagged` can only have explicit discriminants \
+         when a kind is generated with #[sum_type(kind = ...)]"
+    );
+}
@@ -0,0 +1,872 @@
+//! Big enums should compile without raising the recursion limit.
+
+#[macro_use]
+extern crate sum_type;
+
+use sum_type::SumType;
+
+sum_type! {
+    #[derive(Debug, PartialEq)]
+    #[sum_type(kind = DocumentedKind)]
+    pub enum Documented {
+        /// Variant number 0.
+        ///
+        /// Holds 0 bytes.
+        V0([u8; 0]),
+        /// Variant number 1.
+        ///
+        /// Holds 1 bytes.
+        V1([u8; 1]),
+        /// Variant number 2.
+        ///
+        /// Holds 2 bytes.
+        V2([u8; 2]),
+        /// Variant number 3.
+        ///
+        /// Holds 3 bytes.
+        V3([u8; 3]),
+        /// Variant number 4.
+        ///
+        /// Holds 4 bytes.
+        V4([u8; 4]),
+        /// Variant number 5.
+        ///
+        /// Holds 5 bytes.
+        V5([u8; 5]),
+        /// Variant number 6.
+        ///
+        /// Holds 6 bytes.
+        V6([u8; 6]),
+        /// Variant number 7.
+        ///
+        /// Holds 7 bytes.
+        V7([u8; 7]),
+        /// Variant number 8.
+        ///
+        /// Holds 8 bytes.
+        V8([u8; 8]),
+        /// Variant number 9.
+        ///
+        /// Holds 9 bytes.
+        V9([u8; 9]),
+        /// Variant number 10.
+        ///
+        /// Holds 10 bytes.
+        V10([u8; 10]),
+        /// Variant number 11.
+        ///
+        /// Holds 11 bytes.
+        V11([u8; 11]),
+        /// Variant number 12.
+        ///
+        /// Holds 12 bytes.
+        V12([u8; 12]),
+        /// Variant number 13.
+        ///
+        /// Holds 13 bytes.
+        V13([u8; 13]),
+        /// Variant number 14.
+        ///
+        /// Holds 14 bytes.
+        V14([u8; 14]),
+        /// Variant number 15.
+        ///
+        /// Holds 15 bytes.
+        V15([u8; 15]),
+        /// Variant number 16.
+        ///
+        /// Holds 16 bytes.
+        V16([u8; 16]),
+        /// Variant number 17.
+        ///
+        /// Holds 17 bytes.
+        V17([u8; 17]),
+        /// Variant number 18.
+        ///
+        /// Holds 18 bytes.
+        V18([u8; 18]),
+        /// Variant number 19.
+        ///
+        /// Holds 19 bytes.
+        V19([u8; 19]),
+        /// Variant number 20.
+        ///
+        /// Holds 20 bytes.
+        V20([u8; 20]),
+        /// Variant number 21.
+        ///
+        /// Holds 21 bytes.
+        V21([u8; 21]),
+        /// Variant number 22.
+        ///
+        /// Holds 22 bytes.
+        V22([u8; 22]),
+        /// Variant number 23.
+        ///
+        /// Holds 23 bytes.
+        V23([u8; 23]),
+        /// Variant number 24.
+        ///
+        /// Holds 24 bytes.
+        V24([u8; 24]),
+        /// Variant number 25.
+        ///
+        /// Holds 25 bytes.
+        V25([u8; 25]),
+        /// Variant number 26.
+        ///
+        /// Holds 26 bytes.
+        V26([u8; 26]),
+        /// Variant number 27.
+        ///
+        /// Holds 27 bytes.
+        V27([u8; 27]),
+        /// Variant number 28.
+        ///
+        /// Holds 28 bytes.
+        V28([u8; 28]),
+        /// Variant number 29.
+        ///
+        /// Holds 29 bytes.
+        V29([u8; 29]),
+        /// Variant number 30.
+        ///
+        /// Holds 30 bytes.
+        V30([u8; 30]),
+        /// Variant number 31.
+        ///
+        /// Holds 31 bytes.
+        V31([u8; 31]),
+        /// Variant number 32.
+        ///
+        /// Holds 32 bytes.
+        V32([u8; 32]),
+        /// Variant number 33.
+        ///
+        /// Holds 33 bytes.
+        V33([u8; 33]),
+        /// Variant number 34.
+        ///
+        /// Holds 34 bytes.
+        V34([u8; 34]),
+        /// Variant number 35.
+        ///
+        /// Holds 35 bytes.
+        V35([u8; 35]),
+        /// Variant number 36.
+        ///
+        /// Holds 36 bytes.
+        V36([u8; 36]),
+        /// Variant number 37.
+        ///
+        /// Holds 37 bytes.
+        V37([u8; 37]),
+        /// Variant number 38.
+        ///
+        /// Holds 38 bytes.
+        V38([u8; 38]),
+        /// Variant number 39.
+        ///
+        /// Holds 39 bytes.
+        V39([u8; 39]),
+        /// Variant number 40.
+        ///
+        /// Holds 40 bytes.
+        V40([u8; 40]),
+        /// Variant number 41.
+        ///
+        /// Holds 41 bytes.
+        V41([u8; 41]),
+        /// Variant number 42.
+        ///
+        /// Holds 42 bytes.
+        V42([u8; 42]),
+        /// Variant number 43.
+        ///
+        /// Holds 43 bytes.
+        V43([u8; 43]),
+        /// Variant number 44.
+        ///
+        /// Holds 44 bytes.
+        V44([u8; 44]),
+        /// Variant number 45.
+        ///
+        /// Holds 45 bytes.
+        V45([u8; 45]),
+        /// Variant number 46.
+        ///
+        /// Holds 46 bytes.
+        V46([u8; 46]),
+        /// Variant number 47.
+        ///
+        /// Holds 47 bytes.
+        V47([u8; 47]),
+        /// Variant number 48.
+        ///
+        /// Holds 48 bytes.
+        V48([u8; 48]),
+        /// Variant number 49.
+        ///
+        /// Holds 49 bytes.
+        V49([u8; 49]),
+        /// Variant number 50.
+        ///
+        /// Holds 50 bytes.
+        V50([u8; 50]),
+        /// Variant number 51.
+        ///
+        /// Holds 51 bytes.
+        V51([u8; 51]),
+        /// Variant number 52.
+        ///
+        /// Holds 52 bytes.
+        V52([u8; 52]),
+        /// Variant number 53.
+        ///
+        /// Holds 53 bytes.
+        V53([u8; 53]),
+        /// Variant number 54.
+        ///
+        /// Holds 54 bytes.
+        V54([u8; 54]),
+        /// Variant number 55.
+        ///
+        /// Holds 55 bytes.
+        V55([u8; 55]),
+        /// Variant number 56.
+        ///
+        /// Holds 56 bytes.
+        V56([u8; 56]),
+        /// Variant number 57.
+        ///
+        /// Holds 57 bytes.
+        V57([u8; 57]),
+        /// Variant number 58.
+        ///
+        /// Holds 58 bytes.
+        V58([u8; 58]),
+        /// Variant number 59.
+        ///
+        /// Holds 59 bytes.
+        V59([u8; 59]),
+        /// Variant number 60.
+        ///
+        /// Holds 60 bytes.
+        V60([u8; 60]),
+        /// Variant number 61.
+        ///
+        /// Holds 61 bytes.
+        V61([u8; 61]),
+        /// Variant number 62.
+        ///
+        /// Holds 62 bytes.
+        V62([u8; 62]),
+        /// Variant number 63.
+        ///
+        /// Holds 63 bytes.
+        V63([u8; 63]),
+        /// Variant number 64.
+        ///
+        /// Holds 64 bytes.
+        V64([u8; 64]),
+        /// Variant number 65.
+        ///
+        /// Holds 65 bytes.
+        V65([u8; 65]),
+        /// Variant number 66.
+        ///
+        /// Holds 66 bytes.
+        V66([u8; 66]),
+        /// Variant number 67.
+        ///
+        /// Holds 67 bytes.
+        V67([u8; 67]),
+        /// Variant number 68.
+        ///
+        /// Holds 68 bytes.
+        V68([u8; 68]),
+        /// Variant number 69.
+        ///
+        /// Holds 69 bytes.
+        V69([u8; 69]),
+        /// Variant number 70.
+        ///
+        /// Holds 70 bytes.
+        V70([u8; 70]),
+        /// Variant number 71.
+        ///
+        /// Holds 71 bytes.
+        V71([u8; 71]),
+        /// Variant number 72.
+        ///
+        /// Holds 72 bytes.
+        V72([u8; 72]),
+        /// Variant number 73.
+        ///
+        /// Holds 73 bytes.
+        V73([u8; 73]),
+        /// Variant number 74.
+        ///
+        /// Holds 74 bytes.
+        V74([u8; 74]),
+        /// Variant number 75.
+        ///
+        /// Holds 75 bytes.
+        V75([u8; 75]),
+        /// Variant number 76.
+        ///
+        /// Holds 76 bytes.
+        V76([u8; 76]),
+        /// Variant number 77.
+        ///
+        /// Holds 77 bytes.
+        V77([u8; 77]),
+        /// Variant number 78.
+        ///
+        /// Holds 78 bytes.
+        V78([u8; 78]),
+        /// Variant number 79.
+        ///
+        /// Holds 79 bytes.
+        V79([u8; 79]),
+        /// Variant number 80.
+        ///
+        /// Holds 80 bytes.
+        V80([u8; 80]),
+        /// Variant number 81.
+        ///
+        /// Holds 81 bytes.
+        V81([u8; 81]),
+        /// Variant number 82.
+        ///
+        /// Holds 82 bytes.
+        V82([u8; 82]),
+        /// Variant number 83.
+        ///
+        /// Holds 83 bytes.
+        V83([u8; 83]),
+        /// Variant number 84.
+        ///
+        /// Holds 84 bytes.
+        V84([u8; 84]),
+        /// Variant number 85.
+        ///
+        /// Holds 85 bytes.
+        V85([u8; 85]),
+        /// Variant number 86.
+        ///
+        /// Holds 86 bytes.
+        V86([u8; 86]),
+        /// Variant number 87.
+        ///
+        /// Holds 87 bytes.
+        V87([u8; 87]),
+        /// Variant number 88.
+        ///
+        /// Holds 88 bytes.
+        V88([u8; 88]),
+        /// Variant number 89.
+        ///
+        /// Holds 89 bytes.
+        V89([u8; 89]),
+        /// Variant number 90.
+        ///
+        /// Holds 90 bytes.
+        V90([u8; 90]),
+        /// Variant number 91.
+        ///
+        /// Holds 91 bytes.
+        V91([u8; 91]),
+        /// Variant number 92.
+        ///
+        /// Holds 92 bytes.
+        V92([u8; 92]),
+        /// Variant number 93.
+        ///
+        /// Holds 93 bytes.
+        V93([u8; 93]),
+        /// Variant number 94.
+        ///
+        /// Holds 94 bytes.
+        V94([u8; 94]),
+        /// Variant number 95.
+        ///
+        /// Holds 95 bytes.
+        V95([u8; 95]),
+        /// Variant number 96.
+        ///
+        /// Holds 96 bytes.
+        V96([u8; 96]),
+        /// Variant number 97.
+        ///
+        /// Holds 97 bytes.
+        V97([u8; 97]),
+        /// Variant number 98.
+        ///
+        /// Holds 98 bytes.
+        V98([u8; 98]),
+        /// Variant number 99.
+        ///
+        /// Holds 99 bytes.
+        V99([u8; 99]),
+    }
+}
+
+#[test]
+fn hundred_documented_variants() {
+    let value = Documented::from([0_u8; 42]);
+
+    assert_eq!(value.variant(), "V42");
+    assert_eq!(value.variants().len(), 100);
+    assert_eq!(value.kind(), DocumentedKind::V42);
+}
+
+sum_type! {
+    #[derive(Debug, PartialEq)]
+    pub enum Plain {
+        P0([u16; 0]),
+        P1([u16; 1]),
+        P2([u16; 2]),
+        P3([u16; 3]),
+        P4([u16; 4]),
+        P5([u16; 5]),
+        P6([u16; 6]),
+        P7([u16; 7]),
+        P8([u16; 8]),
+        P9([u16; 9]),
+        P10([u16; 10]),
+        P11([u16; 11]),
+        P12([u16; 12]),
+        P13([u16; 13]),
+        P14([u16; 14]),
+        P15([u16; 15]),
+        P16([u16; 16]),
+        P17([u16; 17]),
+        P18([u16; 18]),
+        P19([u16; 19]),
+        P20([u16; 20]),
+        P21([u16; 21]),
+        P22([u16; 22]),
+        P23([u16; 23]),
+        P24([u16; 24]),
+        P25([u16; 25]),
+        P26([u16; 26]),
+        P27([u16; 27]),
+        P28([u16; 28]),
+        P29([u16; 29]),
+        P30([u16; 30]),
+        P31([u16; 31]),
+        P32([u16; 32]),
+        P33([u16; 33]),
+        P34([u16; 34]),
+        P35([u16; 35]),
+        P36([u16; 36]),
+        P37([u16; 37]),
+        P38([u16; 38]),
+        P39([u16; 39]),
+        P40([u16; 40]),
+        P41([u16; 41]),
+        P42([u16; 42]),
+        P43([u16; 43]),
+        P44([u16; 44]),
+        P45([u16; 45]),
+        P46([u16; 46]),
+        P47([u16; 47]),
+        P48([u16; 48]),
+        P49([u16; 49]),
+        P50([u16; 50]),
+        P51([u16; 51]),
+        P52([u16; 52]),
+        P53([u16; 53]),
+        P54([u16; 54]),
+        P55([u16; 55]),
+        P56([u16; 56]),
+        P57([u16; 57]),
+        P58([u16; 58]),
+        P59([u16; 59]),
+        P60([u16; 60]),
+        P61([u16; 61]),
+        P62([u16; 62]),
+        P63([u16; 63]),
+        P64([u16; 64]),
+        P65([u16; 65]),
+        P66([u16; 66]),
+        P67([u16; 67]),
+        P68([u16; 68]),
+        P69([u16; 69]),
+        P70([u16; 70]),
+        P71([u16; 71]),
+        P72([u16; 72]),
+        P73([u16; 73]),
+        P74([u16; 74]),
+        P75([u16; 75]),
+        P76([u16; 76]),
+        P77([u16; 77]),
+        P78([u16; 78]),
+        P79([u16; 79]),
+        P80([u16; 80]),
+        P81([u16; 81]),
+        P82([u16; 82]),
+        P83([u16; 83]),
+        P84([u16; 84]),
+        P85([u16; 85]),
+        P86([u16; 86]),
+        P87([u16; 87]),
+        P88([u16; 88]),
+        P89([u16; 89]),
+        P90([u16; 90]),
+        P91([u16; 91]),
+        P92([u16; 92]),
+        P93([u16; 93]),
+        P94([u16; 94]),
+        P95([u16; 95]),
+        P96([u16; 96]),
+        P97([u16; 97]),
+        P98([u16; 98]),
+        P99([u16; 99]),
+        P100([u16; 100]),
+        P101([u16; 101]),
+        P102([u16; 102]),
+        P103([u16; 103]),
+        P104([u16; 104]),
+        P105([u16; 105]),
+        P106([u16; 106]),
+        P107([u16; 107]),
+        P108([u16; 108]),
+        P109([u16; 109]),
+        P110([u16; 110]),
+        P111([u16; 111]),
+        P112([u16; 112]),
+        P113([u16; 113]),
+        P114([u16; 114]),
+        P115([u16; 115]),
+        P116([u16; 116]),
+        P117([u16; 117]),
+        P118([u16; 118]),
+        P119([u16; 119]),
+    }
+}
+
+#[test]
+fn hundred_and_twenty_plain_variants() {
+    let value = Plain::from([0_u16; 119]);
+
+    assert_eq!(value.variant(), "P119");
+    assert_eq!(value.variants().len(), 120);
+}
+
+// every other variant is configured away
+sum_type! {
+    #[derive(Debug, PartialEq)]
+    pub enum Gated {
+        /// Variant number 0.
+        #[cfg(all())]
+        G0([u32; 0]),
+        /// Variant number 1.
+        #[cfg(any())]
+        G1([u32; 1]),
+        /// Variant number 2.
+        #[cfg(all())]
+        G2([u32; 2]),
+        /// Variant number 3.
+        #[cfg(any())]
+        G3([u32; 3]),
+        /// Variant number 4.
+        #[cfg(all())]
+        G4([u32; 4]),
+        /// Variant number 5.
+        #[cfg(any())]
+        G5([u32; 5]),
+        /// Variant number 6.
+        #[cfg(all())]
+        G6([u32; 6]),
+        /// Variant number 7.
+        #[cfg(any())]
+        G7([u32; 7]),
+        /// Variant number 8.
+        #[cfg(all())]
+        G8([u32; 8]),
+        /// Variant number 9.
+        #[cfg(any())]
+        G9([u32; 9]),
+        /// Variant number 10.
+        #[cfg(all())]
+        G10([u32; 10]),
+        /// Variant number 11.
+        #[cfg(any())]
+        G11([u32; 11]),
+        /// Variant number 12.
+        #[cfg(all())]
+        G12([u32; 12]),
+        /// Variant number 13.
+        #[cfg(any())]
+        G13([u32; 13]),
+        /// Variant number 14.
+        #[cfg(all())]
+        G14([u32; 14]),
+        /// Variant number 15.
+        #[cfg(any())]
+        G15([u32; 15]),
+        /// Variant number 16.
+        #[cfg(all())]
+        G16([u32; 16]),
+        /// Variant number 17.
+        #[cfg(any())]
+        G17([u32; 17]),
+        /// Variant number 18.
+        #[cfg(all())]
+        G18([u32; 18]),
+        /// Variant number 19.
+        #[cfg(any())]
+        G19([u32; 19]),
+        /// Variant number 20.
+        #[cfg(all())]
+        G20([u32; 20]),
+        /// Variant number 21.
+        #[cfg(any())]
+        G21([u32; 21]),
+        /// Variant number 22.
+        #[cfg(all())]
+        G22([u32; 22]),
+        /// Variant number 23.
+        #[cfg(any())]
+        G23([u32; 23]),
+        /// Variant number 24.
+        #[cfg(all())]
+        G24([u32; 24]),
+        /// Variant number 25.
+        #[cfg(any())]
+        G25([u32; 25]),
+        /// Variant number 26.
+        #[cfg(all())]
+        G26([u32; 26]),
+        /// Variant number 27.
+        #[cfg(any())]
+        G27([u32; 27]),
+        /// Variant number 28.
+        #[cfg(all())]
+        G28([u32; 28]),
+        /// Variant number 29.
+        #[cfg(any())]
+        G29([u32; 29]),
+        /// Variant number 30.
+        #[cfg(all())]
+        G30([u32; 30]),
+        /// Variant number 31.
+        #[cfg(any())]
+        G31([u32; 31]),
+        /// Variant number 32.
+        #[cfg(all())]
+        G32([u32; 32]),
+        /// Variant number 33.
+        #[cfg(any())]
+        G33([u32; 33]),
+        /// Variant number 34.
+        #[cfg(all())]
+        G34([u32; 34]),
+        /// Variant number 35.
+        #[cfg(any())]
+        G35([u32; 35]),
+        /// Variant number 36.
+        #[cfg(all())]
+        G36([u32; 36]),
+        /// Variant number 37.
+        #[cfg(any())]
+        G37([u32; 37]),
+        /// Variant number 38.
+        #[cfg(all())]
+        G38([u32; 38]),
+        /// Variant number 39.
+        #[cfg(any())]
+        G39([u32; 39]),
+        /// Variant number 40.
+        #[cfg(all())]
+        G40([u32; 40]),
+        /// Variant number 41.
+        #[cfg(any())]
+        G41([u32; 41]),
+        /// Variant number 42.
+        #[cfg(all())]
+        G42([u32; 42]),
+        /// Variant number 43.
+        #[cfg(any())]
+        G43([u32; 43]),
+        /// Variant number 44.
+        #[cfg(all())]
+        G44([u32; 44]),
+        /// Variant number 45.
+        #[cfg(any())]
+        G45([u32; 45]),
+        /// Variant number 46.
+        #[cfg(all())]
+        G46([u32; 46]),
+        /// Variant number 47.
+        #[cfg(any())]
+        G47([u32; 47]),
+        /// Variant number 48.
+        #[cfg(all())]
+        G48([u32; 48]),
+        /// Variant number 49.
+        #[cfg(any())]
+        G49([u32; 49]),
+        /// Variant number 50.
+        #[cfg(all())]
+        G50([u32; 50]),
+        /// Variant number 51.
+        #[cfg(any())]
+        G51([u32; 51]),
+        /// Variant number 52.
+        #[cfg(all())]
+        G52([u32; 52]),
+        /// Variant number 53.
+        #[cfg(any())]
+        G53([u32; 53]),
+        /// Variant number 54.
+        #[cfg(all())]
+        G54([u32; 54]),
+        /// Variant number 55.
+        #[cfg(any())]
+        G55([u32; 55]),
+        /// Variant number 56.
+        #[cfg(all())]
+        G56([u32; 56]),
+        /// Variant number 57.
+        #[cfg(any())]
+        G57([u32; 57]),
+        /// Variant number 58.
+        #[cfg(all())]
+        G58([u32; 58]),
+        /// Variant number 59.
+        #[cfg(any())]
+        G59([u32; 59]),
+        /// Variant number 60.
+        #[cfg(all())]
+        G60([u32; 60]),
+        /// Variant number 61.
+        #[cfg(any())]
+        G61([u32; 61]),
+        /// Variant number 62.
+        #[cfg(all())]
+        G62([u32; 62]),
+        /// Variant number 63.
+        #[cfg(any())]
+        G63([u32; 63]),
+        /// Variant number 64.
+        #[cfg(all())]
+        G64([u32; 64]),
+        /// Variant number 65.
+        #[cfg(any())]
+        G65([u32; 65]),
+        /// Variant number 66.
+        #[cfg(all())]
+        G66([u32; 66]),
+        /// Variant number 67.
+        #[cfg(any())]
+        G67([u32; 67]),
+        /// Variant number 68.
+        #[cfg(all())]
+        G68([u32; 68]),
+        /// Variant number 69.
+        #[cfg(any())]
+        G69([u32; 69]),
+        /// Variant number 70.
+        #[cfg(all())]
+        G70([u32; 70]),
+        /// Variant number 71.
+        #[cfg(any())]
+        G71([u32; 71]),
+        /// Variant number 72.
+        #[cfg(all())]
+        G72([u32; 72]),
+        /// Variant number 73.
+        #[cfg(any())]
+        G73([u32; 73]),
+        /// Variant number 74.
+        #[cfg(all())]
+        G74([u32; 74]),
+        /// Variant number 75.
+        #[cfg(any())]
+        G75([u32; 75]),
+        /// Variant number 76.
+        #[cfg(all())]
+        G76([u32; 76]),
+        /// Variant number 77.
+        #[cfg(any())]
+        G77([u32; 77]),
+        /// Variant number 78.
+        #[cfg(all())]
+        G78([u32; 78]),
+        /// Variant number 79.
+        #[cfg(any())]
+        G79([u32; 79]),
+        /// Variant number 80.
+        #[cfg(all())]
+        G80([u32; 80]),
+        /// Variant number 81.
+        #[cfg(any())]
+        G81([u32; 81]),
+        /// Variant number 82.
+        #[cfg(all())]
+        G82([u32; 82]),
+        /// Variant number 83.
+        #[cfg(any())]
+        G83([u32; 83]),
+        /// Variant number 84.
+        #[cfg(all())]
+        G84([u32; 84]),
+        /// Variant number 85.
+        #[cfg(any())]
+        G85([u32; 85]),
+        /// Variant number 86.
+        #[cfg(all())]
+        G86([u32; 86]),
+        /// Variant number 87.
+        #[cfg(any())]
+        G87([u32; 87]),
+        /// Variant number 88.
+        #[cfg(all())]
+        G88([u32; 88]),
+        /// Variant number 89.
+        #[cfg(any())]
+        G89([u32; 89]),
+        /// Variant number 90.
+        #[cfg(all())]
+        G90([u32; 90]),
+        /// Variant number 91.
+        #[cfg(any())]
+        G91([u32; 91]),
+        /// Variant number 92.
+        #[cfg(all())]
+        G92([u32; 92]),
+        /// Variant number 93.
+        #[cfg(any())]
+        G93([u32; 93]),
+        /// Variant number 94.
+        #[cfg(all())]
+        G94([u32; 94]),
+        /// Variant number 95.
+        #[cfg(any())]
+        G95([u32; 95]),
+        /// Variant number 96.
+        #[cfg(all())]
+        G96([u32; 96]),
+        /// Variant number 97.
+        #[cfg(any())]
+        G97([u32; 97]),
+        /// Variant number 98.
+        #[cfg(all())]
+        G98([u32; 98]),
+        /// Variant number 99.
+        #[cfg(any())]
+        G99([u32; 99]),
+    }
+}
+
+#[test]
+fn hundred_cfg_gated_variants() {
+    let value = Gated::from([0_u32; 98]);
+
+    assert_eq!(value.variant(), "G98");
+    assert_eq!(value.variants().len(), 50);
+    assert!(!value.variants().contains(&"G99"));
+}